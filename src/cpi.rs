@@ -0,0 +1,409 @@
+//! Helpers for other programs to call the distributor through CPI, in the
+//! style of `pinocchio_system::instructions`. Build with the `no-entrypoint`
+//! feature so this crate's entrypoint does not clash with the caller's.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::{get_return_data, invoke_signed, slice_invoke_signed},
+    instruction::{AccountMeta, Instruction, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    instructions::{
+        self, ClaimAirdropInstructionData, DepositInstructionData, VerifyClaimInstructionData,
+        VerifyClaimResult,
+    },
+    utils::{DataLen, ZeroCopy, MAX_PROOF_LEN},
+};
+
+/// Largest instruction data of `Claim` and `VerifyClaim`: discriminator, the
+/// fixed fields and a proof of `MAX_PROOF_LEN` elements. Both share a layout.
+const MAX_CLAIM_DATA_LEN: usize = 1 + ClaimAirdropInstructionData::LEN + MAX_PROOF_LEN * 32;
+const _: () = assert!(ClaimAirdropInstructionData::LEN == VerifyClaimInstructionData::LEN);
+
+/// Write discriminator, amount, leaf index, bump and the proof into `data`
+/// and return the number of bytes used.
+fn write_claim_data(
+    data: &mut [u8; MAX_CLAIM_DATA_LEN],
+    discriminator: u8,
+    amount: u64,
+    leaf_index: u64,
+    bump: u8,
+    proof: &[[u8; 32]],
+) -> Result<usize, ProgramError> {
+    if proof.len() > MAX_PROOF_LEN {
+        return Err(AirdropProgramError::ProofTooLong.into());
+    }
+
+    data[0] = discriminator;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+    data[9..17].copy_from_slice(&leaf_index.to_le_bytes());
+    data[17] = bump;
+    data[18] = proof.len() as u8;
+
+    let mut offset = 1 + ClaimAirdropInstructionData::LEN;
+    for element in proof {
+        data[offset..offset + 32].copy_from_slice(element);
+        offset += 32;
+    }
+
+    Ok(offset)
+}
+
+/// Claim a leaf with an inline proof.
+///
+/// ### Accounts:
+///   0. `[WRITE]` Airdrop state account
+///   1. `[WRITE]` Vault account
+///   2. `[WRITE, SIGNER]` Claimer, or the claim authority committed in the
+///      leaf; pays the claim-status rent
+///   3. `[WRITE]` Recipient committed in the leaf, or the new pubkey of a
+///      redirect
+///   4. `[WRITE]` Claim status account
+///   5. `[]` System program
+///   6. `[]` Redirect to the recipient, or the empty redirect PDA of the
+///      recipient
+///   7. `[]` Config account
+///   8. `[WRITE]` (optional) Treasury, required once the config exists
+///   9. `[]` (optional) Claimer-keyed status of the leaf's pubkey, required
+///      when the campaign migrated to leaf-keyed statuses
+///  10. `[]` (optional) Instructions sysvar, required when the campaign
+///      restricts CPI callers
+pub struct Claim<'a> {
+    /// Airdrop state account.
+    pub airdrop_state: &'a AccountInfo,
+
+    /// Vault account.
+    pub vault: &'a AccountInfo,
+
+    /// Claimer account.
+    pub signer: &'a AccountInfo,
+
+    /// Recipient account.
+    pub recipient: &'a AccountInfo,
+
+    /// Claim status account.
+    pub user_claim: &'a AccountInfo,
+
+    /// System program account.
+    pub system_program: &'a AccountInfo,
+
+    /// Redirect account.
+    pub redirect: &'a AccountInfo,
+
+    /// Config account.
+    pub config: &'a AccountInfo,
+
+    /// Treasury account.
+    pub treasury: Option<&'a AccountInfo>,
+
+    /// Claimer-keyed claim status account.
+    pub legacy_claim: Option<&'a AccountInfo>,
+
+    /// Instructions sysvar account.
+    pub instructions_sysvar: Option<&'a AccountInfo>,
+
+    /// Amount committed in the leaf.
+    pub amount: u64,
+
+    /// Index of the leaf in the tree.
+    pub leaf_index: u64,
+
+    /// Bump of the claim status account.
+    pub bump: u8,
+
+    /// Merkle proof, at most `MAX_PROOF_LEN` elements.
+    pub proof: &'a [[u8; 32]],
+}
+
+impl Claim<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| {
+            slice_invoke_signed(instruction, accounts, signers)
+        })?
+    }
+
+    /// Build the instruction and hand it to `f` with the account infos in
+    /// the order of its metas, without invoking it.
+    pub fn with_instruction<T>(
+        &self,
+        f: impl FnOnce(&Instruction, &[&AccountInfo]) -> T,
+    ) -> Result<T, ProgramError> {
+        // account metadata; the optional accounts follow the config in order,
+        // and the slots of missing ones are cut off
+        let mut account_metas: [AccountMeta; 11] = [
+            AccountMeta::writable(self.airdrop_state.key()),
+            AccountMeta::writable(self.vault.key()),
+            AccountMeta::writable_signer(self.signer.key()),
+            AccountMeta::writable(self.recipient.key()),
+            AccountMeta::writable(self.user_claim.key()),
+            AccountMeta::readonly(self.system_program.key()),
+            AccountMeta::readonly(self.redirect.key()),
+            AccountMeta::readonly(self.config.key()),
+            AccountMeta::readonly(self.config.key()),
+            AccountMeta::readonly(self.config.key()),
+            AccountMeta::readonly(self.config.key()),
+        ];
+        let mut accounts: [&AccountInfo; 11] = [
+            self.airdrop_state,
+            self.vault,
+            self.signer,
+            self.recipient,
+            self.user_claim,
+            self.system_program,
+            self.redirect,
+            self.config,
+            self.config,
+            self.config,
+            self.config,
+        ];
+        let mut accounts_len = 8;
+        if let Some(treasury) = self.treasury {
+            account_metas[accounts_len] = AccountMeta::writable(treasury.key());
+            accounts[accounts_len] = treasury;
+            accounts_len += 1;
+        }
+        if let Some(legacy_claim) = self.legacy_claim {
+            account_metas[accounts_len] = AccountMeta::readonly(legacy_claim.key());
+            accounts[accounts_len] = legacy_claim;
+            accounts_len += 1;
+        }
+        if let Some(instructions_sysvar) = self.instructions_sysvar {
+            account_metas[accounts_len] = AccountMeta::readonly(instructions_sysvar.key());
+            accounts[accounts_len] = instructions_sysvar;
+            accounts_len += 1;
+        }
+
+        // instruction data
+        // -  [0]: instruction discriminator (1 byte, u8)
+        // -  [1..19]: ClaimAirdropInstructionData
+        // -  [19..]: proof elements (32 bytes each)
+        let mut instruction_data = [0u8; MAX_CLAIM_DATA_LEN];
+        let len = write_claim_data(
+            &mut instruction_data,
+            *instructions::ClaimAirdrop::DISCRIMINATOR,
+            self.amount,
+            self.leaf_index,
+            self.bump,
+            self.proof,
+        )?;
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas[..accounts_len],
+            data: &instruction_data[..len],
+        };
+
+        Ok(f(&instruction, &accounts[..accounts_len]))
+    }
+}
+
+/// Top up the vault of an open campaign.
+///
+/// ### Accounts:
+///   0. `[]` Airdrop state account
+///   1. `[WRITE]` Vault account
+///   2. `[WRITE, SIGNER]` Depositor account
+///   3. `[]` System program
+pub struct Deposit<'a> {
+    /// Airdrop state account.
+    pub airdrop_state: &'a AccountInfo,
+
+    /// Vault account.
+    pub vault: &'a AccountInfo,
+
+    /// Depositor account.
+    pub depositor: &'a AccountInfo,
+
+    /// System program account.
+    pub system_program: &'a AccountInfo,
+
+    /// Amount of lamports to deposit.
+    pub amount: u64,
+}
+
+impl Deposit<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| invoke_signed(instruction, accounts, signers))
+    }
+
+    /// Build the instruction and hand it to `f` with the account infos in
+    /// the order of its metas, without invoking it.
+    pub fn with_instruction<T>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo; 4]) -> T) -> T {
+        // account metadata
+        let account_metas: [AccountMeta; 4] = [
+            AccountMeta::readonly(self.airdrop_state.key()),
+            AccountMeta::writable(self.vault.key()),
+            AccountMeta::writable_signer(self.depositor.key()),
+            AccountMeta::readonly(self.system_program.key()),
+        ];
+
+        // instruction data
+        // -  [0]: instruction discriminator (1 byte, u8)
+        // -  [1..9]: amount (8 bytes, u64)
+        let mut instruction_data = [0u8; 1 + DepositInstructionData::LEN];
+        instruction_data[0] = *instructions::Deposit::DISCRIMINATOR;
+        instruction_data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas,
+            data: &instruction_data,
+        };
+
+        f(
+            &instruction,
+            &[
+                self.airdrop_state,
+                self.vault,
+                self.depositor,
+                self.system_program,
+            ],
+        )
+    }
+}
+
+/// Check a claim without changing any account. Read the outcome with
+/// `VerifyClaim::result` right after the call.
+///
+/// ### Accounts:
+///   0. `[]` Airdrop state account
+///   1. `[]` Recipient committed in the leaf, or the new pubkey of a redirect
+///   2. `[]` Claim status account
+///   3. `[]` Redirect to the recipient, or the empty redirect PDA of the
+///      recipient
+///   4. `[]` Config account
+///   5. `[]` (optional) Claim authority committed in the leaf, required when
+///      the campaign is in `MODE_CLAIM_AUTHORITY`
+///   6. `[]` (optional) Claimer-keyed status of the leaf's pubkey, required
+///      when the campaign migrated to leaf-keyed statuses
+pub struct VerifyClaim<'a> {
+    /// Airdrop state account.
+    pub airdrop_state: &'a AccountInfo,
+
+    /// Recipient account.
+    pub recipient: &'a AccountInfo,
+
+    /// Claim status account.
+    pub user_claim: &'a AccountInfo,
+
+    /// Redirect account.
+    pub redirect: &'a AccountInfo,
+
+    /// Config account.
+    pub config: &'a AccountInfo,
+
+    /// Claim authority account.
+    pub claim_authority: Option<&'a AccountInfo>,
+
+    /// Claimer-keyed claim status account.
+    pub legacy_claim: Option<&'a AccountInfo>,
+
+    /// Amount committed in the leaf.
+    pub amount: u64,
+
+    /// Index of the leaf in the tree.
+    pub leaf_index: u64,
+
+    /// Bump of the claim status account.
+    pub bump: u8,
+
+    /// Merkle proof, at most `MAX_PROOF_LEN` elements.
+    pub proof: &'a [[u8; 32]],
+}
+
+impl VerifyClaim<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| {
+            slice_invoke_signed(instruction, accounts, signers)
+        })?
+    }
+
+    /// Build the instruction and hand it to `f` with the account infos in
+    /// the order of its metas, without invoking it.
+    pub fn with_instruction<T>(
+        &self,
+        f: impl FnOnce(&Instruction, &[&AccountInfo]) -> T,
+    ) -> Result<T, ProgramError> {
+        // account metadata; the optional accounts follow in order, and the
+        // slots of missing ones are cut off
+        let mut account_metas: [AccountMeta; 7] = [
+            AccountMeta::readonly(self.airdrop_state.key()),
+            AccountMeta::readonly(self.recipient.key()),
+            AccountMeta::readonly(self.user_claim.key()),
+            AccountMeta::readonly(self.redirect.key()),
+            AccountMeta::readonly(self.config.key()),
+            AccountMeta::readonly(self.config.key()),
+            AccountMeta::readonly(self.config.key()),
+        ];
+        let mut accounts: [&AccountInfo; 7] = [
+            self.airdrop_state,
+            self.recipient,
+            self.user_claim,
+            self.redirect,
+            self.config,
+            self.config,
+            self.config,
+        ];
+        let mut accounts_len = 5;
+        for account in [self.claim_authority, self.legacy_claim]
+            .into_iter()
+            .flatten()
+        {
+            account_metas[accounts_len] = AccountMeta::readonly(account.key());
+            accounts[accounts_len] = account;
+            accounts_len += 1;
+        }
+
+        // instruction data
+        // -  [0]: instruction discriminator (1 byte, u8)
+        // -  [1..19]: VerifyClaimInstructionData
+        // -  [19..]: proof elements (32 bytes each)
+        let mut instruction_data = [0u8; MAX_CLAIM_DATA_LEN];
+        let len = write_claim_data(
+            &mut instruction_data,
+            *instructions::VerifyClaim::DISCRIMINATOR,
+            self.amount,
+            self.leaf_index,
+            self.bump,
+            self.proof,
+        )?;
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas[..accounts_len],
+            data: &instruction_data[..len],
+        };
+
+        Ok(f(&instruction, &accounts[..accounts_len]))
+    }
+
+    /// Read the `VerifyClaimResult` the last call set as return data.
+    /// `InvalidAccountData` if the distributor did not set it.
+    pub fn result() -> Result<VerifyClaimResult, ProgramError> {
+        let return_data = get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+        if return_data.program_id() != &crate::ID || return_data.len() != VerifyClaimResult::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        VerifyClaimResult::read_from(&return_data).ok_or(ProgramError::InvalidAccountData)
+    }
+}
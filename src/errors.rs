@@ -1,10 +1,52 @@
-use pinocchio::program_error::ProgramError;
+use pinocchio::program_error::{ProgramError, ToStr};
 
-#[derive(Clone, PartialEq)]
+/// Custom errors of the program, returned as `ProgramError::Custom(code)`.
+///
+/// Every code is written out: clients decode failed transactions by number,
+/// so a variant must never be renumbered or reused. New variants take the
+/// next free code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
 pub enum AirdropProgramError {
-    InvalidProof,
-    Unauthorized,
-    AccountAlreadyClaimed,
+    /// The merkle proof does not lead to the campaign root
+    InvalidProof = 0,
+    /// The signer is not allowed to perform this action
+    Unauthorized = 1,
+    /// The leaf or wallet has already claimed
+    AccountAlreadyClaimed = 2,
+    /// The campaign is closed and accepts no further claims
+    AirdropClosed = 3,
+    /// The action needs the campaign to be closed first
+    AirdropNotClosed = 4,
+    /// The hash function is unknown or not compiled into the program
+    UnsupportedHashFunction = 5,
+    /// The signature does not recover the address committed in the leaf
+    InvalidSignature = 6,
+    /// The authority revoked the leaf
+    LeafRevoked = 7,
+    /// An account that must be writable was passed read-only
+    AccountNotWritable = 8,
+    /// An account is not owned by the program
+    InvalidAccountOwner = 9,
+    /// An account that must hold state is empty
+    UninitializedAccount = 10,
+    /// An account is not at the PDA it must be derived at
+    InvalidPda = 11,
+    /// The account passed as the system program is not the system program
+    InvalidSystemProgram = 12,
+    /// An account does not start with the discriminator of the expected type
+    InvalidAccountDiscriminator = 13,
+    /// An account has a layout version this program cannot read
+    UnsupportedAccountVersion = 14,
+    /// The proof has more than `MAX_PROOF_LEN` elements
+    ProofTooLong = 15,
+    // 16 and 17 are unassigned
+    /// An amount does not fit in a u64
+    ArithmeticOverflow = 18,
+    /// The vault holds less than the action would take out of it
+    InsufficientFunds = 19,
+    /// The claim came through a program the campaign's CPI policy rejects
+    CpiNotAllowed = 20,
 }
 
 impl From<AirdropProgramError> for ProgramError {
@@ -12,3 +54,61 @@ impl From<AirdropProgramError> for ProgramError {
         Self::Custom(e as u32)
     }
 }
+
+impl TryFrom<u32> for AirdropProgramError {
+    type Error = ProgramError;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        match code {
+            0 => Ok(Self::InvalidProof),
+            1 => Ok(Self::Unauthorized),
+            2 => Ok(Self::AccountAlreadyClaimed),
+            3 => Ok(Self::AirdropClosed),
+            4 => Ok(Self::AirdropNotClosed),
+            5 => Ok(Self::UnsupportedHashFunction),
+            6 => Ok(Self::InvalidSignature),
+            7 => Ok(Self::LeafRevoked),
+            8 => Ok(Self::AccountNotWritable),
+            9 => Ok(Self::InvalidAccountOwner),
+            10 => Ok(Self::UninitializedAccount),
+            11 => Ok(Self::InvalidPda),
+            12 => Ok(Self::InvalidSystemProgram),
+            13 => Ok(Self::InvalidAccountDiscriminator),
+            14 => Ok(Self::UnsupportedAccountVersion),
+            15 => Ok(Self::ProofTooLong),
+            18 => Ok(Self::ArithmeticOverflow),
+            19 => Ok(Self::InsufficientFunds),
+            20 => Ok(Self::CpiNotAllowed),
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
+
+impl ToStr for AirdropProgramError {
+    fn to_str<E>(&self) -> &'static str
+    where
+        E: 'static + ToStr + TryFrom<u32>,
+    {
+        match self {
+            Self::InvalidProof => "Error: Merkle proof does not match the campaign root",
+            Self::Unauthorized => "Error: Signer is not allowed to perform this action",
+            Self::AccountAlreadyClaimed => "Error: Leaf already claimed",
+            Self::AirdropClosed => "Error: Campaign is closed",
+            Self::AirdropNotClosed => "Error: Campaign must be closed first",
+            Self::UnsupportedHashFunction => "Error: Hash function not supported",
+            Self::InvalidSignature => "Error: Signature does not match the leaf address",
+            Self::LeafRevoked => "Error: Leaf was revoked",
+            Self::AccountNotWritable => "Error: Account must be writable",
+            Self::InvalidAccountOwner => "Error: Account not owned by the program",
+            Self::UninitializedAccount => "Error: Account is not initialized",
+            Self::InvalidPda => "Error: Account is not the expected PDA",
+            Self::InvalidSystemProgram => "Error: Account is not the system program",
+            Self::InvalidAccountDiscriminator => "Error: Account discriminator does not match",
+            Self::UnsupportedAccountVersion => "Error: Account layout version not supported",
+            Self::ProofTooLong => "Error: Proof is too long",
+            Self::ArithmeticOverflow => "Error: Amount overflow",
+            Self::InsufficientFunds => "Error: Vault has insufficient funds",
+            Self::CpiNotAllowed => "Error: Claims through this program are not allowed",
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Events the program logs with `sol_log_data`. Each starts with the first
+//! 8 bytes of `sha256("event:<Name>")`, so Anchor clients can decode the
+//! `Program data:` log lines with an IDL event.
+
+use pinocchio::{log::sol_log_data, pubkey::Pubkey};
+
+use crate::utils::{to_bytes, DataLen, ZeroCopy};
+
+/// Logged for every paid-out leaf of every claim instruction
+#[repr(C, packed)]
+pub struct ClaimEvent {
+    pub discriminator: [u8; 8],
+    pub airdrop: Pubkey,
+    /// Account the funds went to
+    pub recipient: Pubkey,
+    /// Amount committed in the leaf
+    pub amount: u64,
+    /// `NO_LEAF_INDEX` for anonymous claims
+    pub leaf_index: u64,
+    /// Protocol fee sent to the treasury, 0 without a config
+    pub fee: u64,
+}
+
+impl DataLen for ClaimEvent {
+    const LEN: usize = core::mem::size_of::<ClaimEvent>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for ClaimEvent {}
+
+impl ClaimEvent {
+    pub const DISCRIMINATOR: [u8; 8] = [93, 15, 70, 170, 48, 140, 212, 219];
+    /// `ClaimZk` does not reveal which leaf was claimed
+    pub const NO_LEAF_INDEX: u64 = u64::MAX;
+
+    pub fn new(
+        airdrop: &Pubkey,
+        recipient: &Pubkey,
+        amount: u64,
+        leaf_index: u64,
+        fee: u64,
+    ) -> Self {
+        ClaimEvent {
+            discriminator: Self::DISCRIMINATOR,
+            airdrop: *airdrop,
+            recipient: *recipient,
+            amount,
+            leaf_index,
+            fee,
+        }
+    }
+
+    pub fn emit(&self) {
+        // SAFETY: `ZeroCopy` rules out padding
+        sol_log_data(&[unsafe { to_bytes(self) }]);
+    }
+}
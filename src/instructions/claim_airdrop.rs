@@ -1,53 +1,176 @@
-use core::mem::transmute;
-
 use pinocchio::{
-    account_info::AccountInfo,
-    instruction::{Seed, Signer},
-    program_error::ProgramError,
-    sysvars::{rent::Rent, Sysvar},
-    ProgramResult,
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
 };
 
 use crate::{
     errors::AirdropProgramError,
-    states::{AirdropState, ClaimStatus},
+    events::ClaimEvent,
+    states::{
+        existing_claim_status_error, AirdropState, ClaimStatus, LeafClaimStatus, ProofBuffer,
+        ProtocolFee, Redirect,
+    },
     utils::{
-        create_airdrop_leaf, load_acc_mut_unchecked, load_acc_unchecked, verify_merkle_proof,
-        DataLen,
+        proof_from_bytes, verify_merkle_proof, AccountHeader, DataLen, HashFunction, ZeroCopy,
+        MAX_PROOF_LEN,
+    },
+    validation::{
+        check_airdrop_state, check_cpi_policy, check_instructions_sysvar, check_program_owned,
+        check_signer, check_system_program, check_vault, check_writable,
     },
 };
 
 pub struct ClaimAirdropAccounts<'info> {
     pub airdrop_state: &'info AccountInfo,
+    pub vault: &'info AccountInfo,
+    /// Executes the claim and pays the claim-status rent
     pub signer: &'info AccountInfo,
+    /// The pubkey committed in the leaf, or the new pubkey of a redirect;
+    /// always receives the funds
+    pub recipient: &'info AccountInfo,
     pub user_claim: &'info AccountInfo,
+    /// Redirect to `recipient`, or the empty redirect PDA of `recipient`
+    pub redirect: &'info AccountInfo,
+    /// Config and treasury
+    pub fee: ProtocolFee<'info>,
+    /// Claimer-keyed status of the leaf's pubkey, required on campaigns
+    /// migrated to leaf-keyed statuses
+    pub legacy_claim: Option<&'info AccountInfo>,
+    /// Optional proof buffer, used instead of the inline proof when present
+    pub proof_buffer: Option<&'info AccountInfo>,
+    /// Instructions sysvar, required when the campaign restricts CPI callers
+    pub instructions_sysvar: Option<&'info AccountInfo>,
 }
 
-impl<'info> TryFrom<&'info [AccountInfo]> for ClaimAirdropAccounts<'info> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
-        let [airdrop_state, signer, user_claim, _] = accounts else {
+impl<'info> ClaimAirdropAccounts<'info> {
+    /// Check the accounts every claim takes, in order: the fixed ones, the
+    /// treasury once the config exists and the claimer-keyed status on
+    /// migrated campaigns. Returns the accounts left over.
+    pub fn parse(
+        accounts: &'info [AccountInfo],
+    ) -> Result<(Self, &'info [AccountInfo]), ProgramError> {
+        let [airdrop_state, vault, signer, recipient, user_claim, system_program, redirect, config, remaining @ ..] =
+            accounts
+        else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        // verify airdrop_state
-        if !airdrop_state.is_writable() || airdrop_state.data_len() == 0 {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        check_airdrop_state(airdrop_state, true)?;
+        check_vault(vault)?;
 
-        if !user_claim.is_writable() {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        let (fee, remaining) = ProtocolFee::parse(config, remaining)?;
+
+        // verify signer may claim for recipient under the campaign's mode
+        check_signer(signer)?;
+        check_writable(recipient)?;
+        let is_migrated = {
+            let airdrop_state = AirdropState::load(airdrop_state)?;
+            if !airdrop_state.can_claim_for(signer.key(), recipient.key()) {
+                return Err(AirdropProgramError::Unauthorized.into());
+            }
+            airdrop_state.is_migrated_to_leaf()
+        };
+
+        // checked against the leaf's pubkey once it is known
+        let (legacy_claim, remaining) = if is_migrated {
+            let [legacy_claim, remaining @ ..] = remaining else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            (Some(legacy_claim), remaining)
+        } else {
+            (None, remaining)
+        };
+
+        check_writable(user_claim)?;
         if !user_claim.data_is_empty() {
-            return Err(AirdropProgramError::AccountAlreadyClaimed.into());
+            return Err(existing_claim_status_error(user_claim).into());
         }
 
-        Ok(ClaimAirdropAccounts {
+        check_system_program(system_program)?;
+
+        let accounts = ClaimAirdropAccounts {
             airdrop_state,
+            vault,
             signer,
+            recipient,
             user_claim,
-        })
+            redirect,
+            fee,
+            legacy_claim,
+            proof_buffer: None,
+            instructions_sysvar: None,
+        };
+        Ok((accounts, remaining))
+    }
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for ClaimAirdropAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let (mut accounts, remaining) = ClaimAirdropAccounts::parse(accounts)?;
+
+        // optional accounts: the instructions sysvar is told apart by its key,
+        // anything else is the proof buffer
+        for account in remaining {
+            if check_instructions_sysvar(account).is_ok() {
+                if accounts.instructions_sysvar.replace(account).is_some() {
+                    return Err(ProgramError::InvalidArgument);
+                }
+                continue;
+            }
+
+            check_program_owned(account)?;
+            check_writable(account)?;
+            if accounts.proof_buffer.replace(account).is_some() {
+                return Err(ProgramError::InvalidArgument);
+            }
+        }
+
+        Ok(accounts)
+    }
+}
+
+impl ClaimAirdropAccounts<'_> {
+    /// Protocol fee owed on a claim of `amount`, 0 until the config exists
+    pub fn protocol_fee(&self, amount: u64) -> Result<u64, ProgramError> {
+        self.fee.fee_for(amount)
+    }
+
+    /// Pubkey committed in the leaves paid to the recipient: the old pubkey
+    /// of its redirect, or the recipient itself. A reassigned recipient can
+    /// no longer claim.
+    pub fn leaf_pubkey(&self) -> Result<Pubkey, ProgramError> {
+        Redirect::leaf_pubkey(
+            self.redirect,
+            self.airdrop_state.key(),
+            self.recipient.key(),
+        )?
+        .ok_or(AirdropProgramError::Unauthorized.into())
+    }
+
+    /// Fail if the leaves of `claimer` were claimed or revoked before the
+    /// campaign migrated to leaf-keyed statuses
+    pub fn check_legacy_claim(&self, claimer: &Pubkey) -> ProgramResult {
+        let Some(legacy_claim) = self.legacy_claim else {
+            return Ok(());
+        };
+        let seeds: &[&[u8]] = &[
+            ClaimStatus::SEED,
+            self.airdrop_state.key().as_ref(),
+            claimer.as_ref(),
+        ];
+        match ClaimStatus::prior_claim(legacy_claim, seeds)? {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
+        }
+    }
+
+    /// Move `amount` from the vault to the recipient and `fee` to the
+    /// treasury, either withheld from `amount` or paid by the signer as the
+    /// config says.
+    pub fn pay_out(&self, amount: u64, fee: u64) -> ProgramResult {
+        self.fee
+            .pay_out(self.vault, self.signer, self.recipient, amount, fee)
     }
 }
 
@@ -63,6 +186,9 @@ impl DataLen for ClaimAirdropInstructionData {
     const LEN: usize = core::mem::size_of::<ClaimAirdropInstructionData>();
 }
 
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for ClaimAirdropInstructionData {}
+
 impl<'info> TryFrom<&'info [u8]> for ClaimAirdropInstructionData {
     type Error = ProgramError;
 
@@ -73,12 +199,7 @@ impl<'info> TryFrom<&'info [u8]> for ClaimAirdropInstructionData {
 
         let fixed_data = &data[..Self::LEN];
 
-        Ok(unsafe {
-            transmute(
-                TryInto::<[u8; Self::LEN]>::try_into(fixed_data)
-                    .map_err(|_| ProgramError::InvalidInstructionData)?,
-            )
-        })
+        Self::read_from(fixed_data).ok_or(ProgramError::InvalidInstructionData)
     }
 }
 
@@ -100,6 +221,9 @@ impl<'info> ClaimAirdrop<'info> {
         // calculate offset for proof data
         let proof_offset = ClaimAirdropInstructionData::LEN;
         let proof_len = instruction_data.proof_len as usize;
+        if proof_len > MAX_PROOF_LEN {
+            return Err(AirdropProgramError::ProofTooLong.into());
+        }
 
         // check data length
         let expected_len = proof_offset + (proof_len * 32);
@@ -107,11 +231,14 @@ impl<'info> ClaimAirdrop<'info> {
             return Err(ProgramError::InvalidInstructionData);
         }
 
+        // the proof comes either inline or from the buffer, never both
+        if accounts.proof_buffer.is_some() && proof_len != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
         // Parse proof data as slice of [u8; 32]
-        let proof_bytes = &data[proof_offset..];
-        let proof_data = unsafe {
-            core::slice::from_raw_parts(proof_bytes.as_ptr() as *const [u8; 32], proof_len)
-        };
+        let proof_data =
+            proof_from_bytes(&data[proof_offset..]).ok_or(ProgramError::InvalidInstructionData)?;
 
         Ok(ClaimAirdrop {
             accounts,
@@ -138,6 +265,8 @@ impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for ClaimAirdrop<'info>
 
 impl<'info> ClaimAirdrop<'info> {
     pub const DISCRIMINATOR: &'info u8 = &1;
+    /// `sha256("global:claim_airdrop")[..8]`
+    pub const ANCHOR_DISCRIMINATOR: [u8; 8] = [137, 50, 122, 111, 89, 254, 8, 20];
 
     pub fn process(&mut self) -> ProgramResult {
         // Get proof data
@@ -145,71 +274,103 @@ impl<'info> ClaimAirdrop<'info> {
         let amount = self.instruction_data.amount;
         let leaf_index = self.instruction_data.leaf_index;
 
-        // Create leaf hash
-        let claimer = *self.accounts.signer.key();
-        let leaf = create_airdrop_leaf(&claimer, amount, 0);
-        let airdrop_state = unsafe {
-            load_acc_unchecked::<AirdropState>(self.accounts.airdrop_state.borrow_data_unchecked())
-        }?;
-        let merkle_root = airdrop_state.merkle_root;
+        // Create leaf hash; a redirected leaf still commits to the old pubkey
+        let claimer = self.accounts.leaf_pubkey()?;
+        let (merkle_root, keyed_by_leaf, uses_claim_authority, hash_fn) = {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+            if airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+            check_cpi_policy(&airdrop_state, self.accounts.instructions_sysvar)?;
+            airdrop_state
+                .validate_vault(self.accounts.vault.key(), self.accounts.airdrop_state.key())?;
+            (
+                airdrop_state.merkle_root,
+                airdrop_state.is_keyed_by_leaf(),
+                airdrop_state.uses_claim_authority(),
+                HashFunction::try_from(airdrop_state.hash_fn[0])?,
+            )
+        };
+        // a claim authority leaf is only found when the signer is the
+        // committed claim authority
+        let leaf = if uses_claim_authority {
+            hash_fn.create_delegated_airdrop_leaf(&claimer, self.accounts.signer.key(), amount, 0)
+        } else {
+            hash_fn.create_airdrop_leaf(&claimer, amount, 0)
+        }
+        .ok_or(AirdropProgramError::InvalidProof)?;
 
         // Verify merkle proof
-        let is_valid = verify_merkle_proof(&leaf, proof, leaf_index, &merkle_root);
+        let is_valid = match self.accounts.proof_buffer {
+            Some(proof_buffer) => {
+                let data = proof_buffer.try_borrow_data()?;
+                let (header, buffered_proof) = ProofBuffer::unpack(&data)?;
+                ProofBuffer::validate_pda(
+                    proof_buffer.key(),
+                    self.accounts.airdrop_state.key(),
+                    self.accounts.signer.key(),
+                    header.bump[0],
+                )?;
+                verify_merkle_proof(hash_fn, &leaf, buffered_proof, leaf_index, &merkle_root)
+            }
+            None => verify_merkle_proof(hash_fn, &leaf, proof, leaf_index, &merkle_root),
+        };
 
         if !is_valid {
             return Err(AirdropProgramError::InvalidProof.into());
         }
 
-        ClaimStatus::validate_pda(
-            self.accounts.user_claim.key(),
-            self.accounts.airdrop_state.key(),
-            self.accounts.signer.key(),
-            self.instruction_data.bump,
-        )?;
-
-        // // init user_claim to avoid double claims
-        {
-            let bump_binding = [self.instruction_data.bump];
-            let seed = [
-                Seed::from(ClaimStatus::SEED),
-                Seed::from(self.accounts.airdrop_state.key().as_ref()),
-                Seed::from(self.accounts.signer.key().as_ref()),
-                Seed::from(&bump_binding),
-            ];
-            let signer_seeds = Signer::from(&seed);
-
-            pinocchio_system::instructions::CreateAccount {
-                from: self.accounts.signer,
-                to: self.accounts.user_claim,
-                space: ClaimStatus::LEN as u64,
-                lamports: Rent::get()?.minimum_balance(ClaimStatus::LEN),
-                owner: &crate::ID,
-            }
-            .invoke_signed(&[signer_seeds])?;
-
-            let mut data: pinocchio::account_info::RefMut<'_, [u8]> =
-                self.accounts.user_claim.try_borrow_mut_data()?;
-            let user_claim = unsafe { load_acc_mut_unchecked::<ClaimStatus>(&mut data) }?;
-
-            user_claim.bump = [self.instruction_data.bump];
+        // init user_claim to avoid double claims
+        if keyed_by_leaf {
+            self.accounts.check_legacy_claim(&claimer)?;
+            LeafClaimStatus::init(
+                self.accounts.signer,
+                self.accounts.user_claim,
+                self.accounts.airdrop_state.key(),
+                leaf_index,
+                self.instruction_data.bump,
+            )?;
+        } else {
+            ClaimStatus::init(
+                self.accounts.signer,
+                self.accounts.user_claim,
+                [
+                    ClaimStatus::SEED,
+                    self.accounts.airdrop_state.key().as_ref(),
+                    claimer.as_ref(),
+                ],
+                self.instruction_data.bump,
+            )?;
         }
 
+        let fee = self.accounts.protocol_fee(amount)?;
+        self.accounts.pay_out(amount, fee)?;
+
         {
-            *self.accounts.airdrop_state.try_borrow_mut_lamports()? -= amount;
-            *self.accounts.signer.try_borrow_mut_lamports()? += amount;
+            let mut airdrop_state = AirdropState::load_mut(self.accounts.airdrop_state)?;
+            let amount_claimed = airdrop_state.amount_claimed.get();
+            airdrop_state
+                .amount_claimed
+                .set(amount_claimed.saturating_add(amount));
         }
 
-        {
-            let airdrop_state = unsafe {
-                load_acc_mut_unchecked::<AirdropState>(
-                    self.accounts.airdrop_state.borrow_mut_data_unchecked(),
-                )
-            }?;
-            airdrop_state.amount_claimed = (u64::from_le_bytes(airdrop_state.amount_claimed)
-                .saturating_add(amount))
-            .to_be_bytes();
+        if let Some(proof_buffer) = self.accounts.proof_buffer {
+            // the buffer is single use; refund its rent to the claimer
+            let rent = proof_buffer.lamports();
+            *proof_buffer.try_borrow_mut_lamports()? -= rent;
+            *self.accounts.signer.try_borrow_mut_lamports()? += rent;
+            proof_buffer.close()?;
         }
 
+        ClaimEvent::new(
+            self.accounts.airdrop_state.key(),
+            self.accounts.recipient.key(),
+            amount,
+            leaf_index,
+            fee,
+        )
+        .emit();
+
         Ok(())
     }
 }
@@ -0,0 +1,271 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{
+    errors::AirdropProgramError,
+    events::ClaimEvent,
+    instructions::ClaimAirdropAccounts,
+    states::{existing_claim_status_error, AirdropState, ClaimStatus, LeafClaimStatus},
+    utils::{
+        proof_from_bytes, verify_merkle_multiproof, AccountHeader, DataLen, HashFunction, ZeroCopy,
+        MAX_MULTIPROOF_LEAVES,
+    },
+    validation::{check_cpi_policy, check_instructions_sysvar, check_writable},
+};
+
+#[repr(C, packed)]
+pub struct ClaimBatchInstructionData {
+    pub bump: u8,
+    pub count: u8,
+    pub proof_len: u8,
+}
+
+impl DataLen for ClaimBatchInstructionData {
+    const LEN: usize = core::mem::size_of::<ClaimBatchInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for ClaimBatchInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for ClaimBatchInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let fixed_data = &data[..Self::LEN];
+
+        Self::read_from(fixed_data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// One claimed leaf of the batch.
+#[repr(C, packed)]
+pub struct ClaimRecord {
+    pub amount: u64,
+    pub leaf_index: u64,
+}
+
+impl DataLen for ClaimRecord {
+    const LEN: usize = core::mem::size_of::<ClaimRecord>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for ClaimRecord {}
+
+/// Claims several leaves of the recipient in one instruction. All leaves are
+/// checked against the root with a single multiproof, then the total is paid
+/// in one transfer.
+///
+/// Data layout after the header: `count` records, `count + proof_len - 1`
+/// proof flags (one byte each, 0 or 1), then `proof_len` proof elements.
+///
+/// When the campaign keys claim statuses by leaf index, every record needs its
+/// own status: `user_claim` and the header bump belong to the first record,
+/// and the remaining `count - 1` statuses come last in the accounts, with
+/// their bumps appended to the data.
+pub struct ClaimBatch<'info> {
+    pub accounts: ClaimAirdropAccounts<'info>,
+    pub instruction_data: ClaimBatchInstructionData,
+    pub records: &'info [ClaimRecord],
+    pub proof_flags: &'info [bool],
+    pub proof_data: &'info [[u8; 32]],
+    /// Leaf-keyed statuses of records `1..count`
+    pub extra_claims: &'info [AccountInfo],
+    pub extra_bumps: &'info [u8],
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for ClaimBatch<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        // proofs are always inline for batch claims, so every account after
+        // the instructions sysvar, if passed, is an extra claim status
+        let (mut accounts, remaining) = ClaimAirdropAccounts::parse(accounts)?;
+        let extra_claims = match remaining.split_first() {
+            Some((account, extra_claims)) if check_instructions_sysvar(account).is_ok() => {
+                accounts.instructions_sysvar = Some(account);
+                extra_claims
+            }
+            _ => remaining,
+        };
+        let instruction_data = ClaimBatchInstructionData::try_from(data)?;
+
+        for user_claim in extra_claims {
+            check_writable(user_claim)?;
+            if !user_claim.data_is_empty() {
+                return Err(existing_claim_status_error(user_claim).into());
+            }
+        }
+
+        let count = instruction_data.count as usize;
+        let proof_len = instruction_data.proof_len as usize;
+        if count == 0 || count > MAX_MULTIPROOF_LEAVES {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let flags_len = count + proof_len - 1;
+
+        // check data length
+        let records_offset = ClaimBatchInstructionData::LEN;
+        let flags_offset = records_offset + count * ClaimRecord::LEN;
+        let proof_offset = flags_offset + flags_len;
+        let bumps_offset = proof_offset + proof_len * 32;
+        if data.len() != bumps_offset + extra_claims.len() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let flag_bytes = &data[flags_offset..proof_offset];
+        if flag_bytes.iter().any(|flag| *flag > 1) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let records = ClaimRecord::slice_from(&data[records_offset..flags_offset])
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        // every byte was checked to be 0 or 1 above
+        let proof_flags =
+            unsafe { core::slice::from_raw_parts(flag_bytes.as_ptr() as *const bool, flags_len) };
+        let proof_data = proof_from_bytes(&data[proof_offset..bumps_offset])
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok(ClaimBatch {
+            accounts,
+            instruction_data,
+            records,
+            proof_flags,
+            proof_data,
+            extra_claims,
+            extra_bumps: &data[bumps_offset..],
+        })
+    }
+}
+
+impl<'info> ClaimBatch<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &5;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let claimer = self.accounts.leaf_pubkey()?;
+        let (merkle_root, hash_fn, keyed_by_leaf, uses_claim_authority) = {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+            if airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+            check_cpi_policy(&airdrop_state, self.accounts.instructions_sysvar)?;
+            airdrop_state
+                .validate_vault(self.accounts.vault.key(), self.accounts.airdrop_state.key())?;
+            (
+                airdrop_state.merkle_root,
+                HashFunction::try_from(airdrop_state.hash_fn[0])?,
+                airdrop_state.is_keyed_by_leaf(),
+                airdrop_state.uses_claim_authority(),
+            )
+        };
+
+        // one status per record when keyed by leaf, a single one otherwise
+        let expected_extra_claims = if keyed_by_leaf {
+            self.records.len() - 1
+        } else {
+            0
+        };
+        if self.extra_claims.len() != expected_extra_claims {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        // Verify every leaf with one multiproof; it also requires strictly
+        // increasing indices so no leaf is counted twice within the batch
+        let mut leaves = [[0u8; 32]; MAX_MULTIPROOF_LEAVES];
+        let mut leaf_indices = [0u64; MAX_MULTIPROOF_LEAVES];
+        let mut total: u64 = 0;
+        for (i, record) in self.records.iter().enumerate() {
+            let amount = record.amount;
+            leaves[i] = if uses_claim_authority {
+                hash_fn.create_delegated_airdrop_leaf(
+                    &claimer,
+                    self.accounts.signer.key(),
+                    amount,
+                    0,
+                )
+            } else {
+                hash_fn.create_airdrop_leaf(&claimer, amount, 0)
+            }
+            .ok_or(AirdropProgramError::InvalidProof)?;
+            leaf_indices[i] = record.leaf_index;
+            total = total
+                .checked_add(amount)
+                .ok_or(AirdropProgramError::ArithmeticOverflow)?;
+        }
+
+        let count = self.records.len();
+        if !verify_merkle_multiproof(
+            hash_fn,
+            &leaves[..count],
+            &leaf_indices[..count],
+            self.proof_data,
+            self.proof_flags,
+            &merkle_root,
+        ) {
+            return Err(AirdropProgramError::InvalidProof.into());
+        }
+
+        // init the claim statuses to avoid double claims
+        if keyed_by_leaf {
+            self.accounts.check_legacy_claim(&claimer)?;
+            let user_claims = core::iter::once(self.accounts.user_claim).chain(self.extra_claims);
+            let bumps = core::iter::once(&self.instruction_data.bump).chain(self.extra_bumps);
+            for ((record, user_claim), bump) in self.records.iter().zip(user_claims).zip(bumps) {
+                LeafClaimStatus::init(
+                    self.accounts.signer,
+                    user_claim,
+                    self.accounts.airdrop_state.key(),
+                    record.leaf_index,
+                    *bump,
+                )?;
+            }
+        } else {
+            ClaimStatus::init(
+                self.accounts.signer,
+                self.accounts.user_claim,
+                [
+                    ClaimStatus::SEED,
+                    self.accounts.airdrop_state.key().as_ref(),
+                    claimer.as_ref(),
+                ],
+                self.instruction_data.bump,
+            )?;
+        }
+
+        // the fee applies per leaf, so a flat fee cannot be avoided by batching
+        let mut fees = [0u64; MAX_MULTIPROOF_LEAVES];
+        for (fee, record) in fees.iter_mut().zip(self.records) {
+            *fee = self.accounts.protocol_fee(record.amount)?;
+        }
+        let total_fee = fees[..count]
+            .iter()
+            .try_fold(0u64, |sum, fee| sum.checked_add(*fee))
+            .ok_or(AirdropProgramError::ArithmeticOverflow)?;
+        self.accounts.pay_out(total, total_fee)?;
+
+        {
+            let mut airdrop_state = AirdropState::load_mut(self.accounts.airdrop_state)?;
+            let amount_claimed = airdrop_state.amount_claimed.get();
+            airdrop_state
+                .amount_claimed
+                .set(amount_claimed.saturating_add(total));
+        }
+
+        for (record, fee) in self.records.iter().zip(fees) {
+            ClaimEvent::new(
+                self.accounts.airdrop_state.key(),
+                self.accounts.recipient.key(),
+                record.amount,
+                record.leaf_index,
+                fee,
+            )
+            .emit();
+        }
+
+        Ok(())
+    }
+}
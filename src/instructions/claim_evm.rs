@@ -0,0 +1,284 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    events::ClaimEvent,
+    states::{
+        existing_claim_status_error, AirdropState, ClaimStatus, LeafClaimStatus, ProtocolFee,
+    },
+    utils::{
+        proof_from_bytes, recover_evm_address, verify_merkle_proof, AccountHeader, DataLen,
+        HashFunction, ZeroCopy, MAX_PROOF_LEN,
+    },
+    validation::{
+        check_airdrop_state, check_cpi_policy, check_instructions_sysvar, check_signer,
+        check_system_program, check_vault, check_writable,
+    },
+};
+
+pub struct ClaimEvmAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub vault: &'info AccountInfo,
+    /// Submits the claim and pays the claim-status rent
+    pub payer: &'info AccountInfo,
+    /// Solana account chosen by the EVM signer to receive the funds
+    pub destination: &'info AccountInfo,
+    pub user_claim: &'info AccountInfo,
+    /// Config and treasury
+    pub fee: ProtocolFee<'info>,
+    /// `evm_claim` status of the address, required on campaigns migrated to
+    /// leaf-keyed statuses
+    pub legacy_claim: Option<&'info AccountInfo>,
+    /// Instructions sysvar, required when the campaign restricts CPI callers
+    pub instructions_sysvar: Option<&'info AccountInfo>,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for ClaimEvmAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, vault, payer, destination, user_claim, system_program, config, remaining @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, true)?;
+        check_vault(vault)?;
+
+        let (fee, remaining) = ProtocolFee::parse(config, remaining)?;
+
+        // the claimer-keyed status follows on migrated campaigns, then the
+        // instructions sysvar if passed
+        let (legacy_claim, remaining) = if AirdropState::load(airdrop_state)?.is_migrated_to_leaf()
+        {
+            let [legacy_claim, remaining @ ..] = remaining else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            (Some(legacy_claim), remaining)
+        } else {
+            (None, remaining)
+        };
+        let instructions_sysvar = match remaining {
+            [] => None,
+            [instructions_sysvar] => {
+                check_instructions_sysvar(instructions_sysvar)?;
+                Some(instructions_sysvar)
+            }
+            _ => return Err(ProgramError::InvalidArgument),
+        };
+
+        check_signer(payer)?;
+        check_writable(payer)?;
+        check_writable(destination)?;
+
+        check_writable(user_claim)?;
+        if !user_claim.data_is_empty() {
+            return Err(existing_claim_status_error(user_claim).into());
+        }
+
+        check_system_program(system_program)?;
+
+        Ok(ClaimEvmAccounts {
+            airdrop_state,
+            vault,
+            payer,
+            destination,
+            user_claim,
+            fee,
+            legacy_claim,
+            instructions_sysvar,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct ClaimEvmInstructionData {
+    pub amount: u64,
+    pub leaf_index: u64,
+    /// secp256k1 signature over `evm_claim_message_hash`
+    pub signature: [u8; 64],
+    pub recovery_id: u8,
+    pub bump: u8,
+    pub proof_len: u8,
+}
+
+impl DataLen for ClaimEvmInstructionData {
+    const LEN: usize = core::mem::size_of::<ClaimEvmInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for ClaimEvmInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for ClaimEvmInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let fixed_data = &data[..Self::LEN];
+
+        Self::read_from(fixed_data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// EIP-191 `personal_sign` prefix for the 72-byte claim message
+const EVM_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n72";
+
+/// Hash the EVM holder signs to claim `amount` of `airdrop` to `destination`:
+/// `personal_sign(airdrop || destination || amount_le)`.
+pub fn evm_claim_message_hash(airdrop: &Pubkey, destination: &Pubkey, amount: u64) -> [u8; 32] {
+    solana_nostd_keccak::hashv(&[
+        EVM_MESSAGE_PREFIX,
+        airdrop.as_ref(),
+        destination.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+}
+
+/// Claims a leaf committed to an EVM address. The address is recovered from
+/// a secp256k1 signature, so anyone may submit the transaction.
+pub struct ClaimEvm<'info> {
+    pub accounts: ClaimEvmAccounts<'info>,
+    pub instruction_data: ClaimEvmInstructionData,
+    pub proof_data: &'info [[u8; 32]],
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for ClaimEvm<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = ClaimEvmAccounts::try_from(accounts)?;
+        let instruction_data = ClaimEvmInstructionData::try_from(data)?;
+
+        // check data length
+        let proof_offset = ClaimEvmInstructionData::LEN;
+        let proof_len = instruction_data.proof_len as usize;
+        if proof_len > MAX_PROOF_LEN {
+            return Err(AirdropProgramError::ProofTooLong.into());
+        }
+        if data.len() != proof_offset + proof_len * 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let proof_data =
+            proof_from_bytes(&data[proof_offset..]).ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok(ClaimEvm {
+            accounts,
+            instruction_data,
+            proof_data,
+        })
+    }
+}
+
+impl<'info> ClaimEvm<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &7;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let amount = self.instruction_data.amount;
+        let leaf_index = self.instruction_data.leaf_index;
+
+        let (merkle_root, hash_fn, keyed_by_leaf) = {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+            if airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+            check_cpi_policy(&airdrop_state, self.accounts.instructions_sysvar)?;
+            airdrop_state
+                .validate_vault(self.accounts.vault.key(), self.accounts.airdrop_state.key())?;
+            (
+                airdrop_state.merkle_root,
+                HashFunction::try_from(airdrop_state.hash_fn[0])?,
+                airdrop_state.is_keyed_by_leaf(),
+            )
+        };
+
+        // Recover the EVM address that authorized this destination and amount
+        let message_hash = evm_claim_message_hash(
+            self.accounts.airdrop_state.key(),
+            self.accounts.destination.key(),
+            amount,
+        );
+        let address = recover_evm_address(
+            &message_hash,
+            self.instruction_data.recovery_id,
+            &self.instruction_data.signature,
+        )
+        .ok_or(AirdropProgramError::InvalidSignature)?;
+
+        // Verify merkle proof
+        let leaf = hash_fn
+            .create_evm_airdrop_leaf(&address, amount, 0)
+            .ok_or(AirdropProgramError::InvalidProof)?;
+        if !verify_merkle_proof(hash_fn, &leaf, self.proof_data, leaf_index, &merkle_root) {
+            return Err(AirdropProgramError::InvalidProof.into());
+        }
+
+        // init user_claim to avoid double claims
+        if keyed_by_leaf {
+            if let Some(legacy_claim) = self.accounts.legacy_claim {
+                let seeds: &[&[u8]] = &[
+                    ClaimStatus::EVM_SEED,
+                    self.accounts.airdrop_state.key().as_ref(),
+                    address.as_ref(),
+                ];
+                if let Some(error) = ClaimStatus::prior_claim(legacy_claim, seeds)? {
+                    return Err(error.into());
+                }
+            }
+            LeafClaimStatus::init(
+                self.accounts.payer,
+                self.accounts.user_claim,
+                self.accounts.airdrop_state.key(),
+                leaf_index,
+                self.instruction_data.bump,
+            )?;
+        } else {
+            ClaimStatus::init(
+                self.accounts.payer,
+                self.accounts.user_claim,
+                [
+                    ClaimStatus::EVM_SEED,
+                    self.accounts.airdrop_state.key().as_ref(),
+                    address.as_ref(),
+                ],
+                self.instruction_data.bump,
+            )?;
+        }
+
+        let fee = self.accounts.fee.fee_for(amount)?;
+        self.accounts.fee.pay_out(
+            self.accounts.vault,
+            self.accounts.payer,
+            self.accounts.destination,
+            amount,
+            fee,
+        )?;
+
+        {
+            let mut airdrop_state = AirdropState::load_mut(self.accounts.airdrop_state)?;
+            let amount_claimed = airdrop_state.amount_claimed.get();
+            airdrop_state
+                .amount_claimed
+                .set(amount_claimed.saturating_add(amount));
+        }
+
+        ClaimEvent::new(
+            self.accounts.airdrop_state.key(),
+            self.accounts.destination.key(),
+            amount,
+            leaf_index,
+            fee,
+        )
+        .emit();
+
+        Ok(())
+    }
+}
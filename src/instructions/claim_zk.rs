@@ -0,0 +1,270 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    events::ClaimEvent,
+    states::{AirdropState, Nullifier, ProtocolFee, ZkConfig},
+    utils::{verify_groth16, zk_field_element, AccountHeader, DataLen, ZeroCopy, ZK_PUBLIC_INPUTS},
+    validation::{
+        check_airdrop_state, check_cpi_policy, check_initialized, check_instructions_sysvar,
+        check_program_owned, check_signer, check_system_program, check_vault, check_writable,
+    },
+};
+
+pub struct ClaimZkAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub vault: &'info AccountInfo,
+    pub zk_config: &'info AccountInfo,
+    /// Submits the claim and pays the nullifier rent; may be a relayer
+    pub payer: &'info AccountInfo,
+    /// Any account chosen by the claimer, bound by the proof
+    pub destination: &'info AccountInfo,
+    pub nullifier: &'info AccountInfo,
+    /// Config and treasury
+    pub fee: ProtocolFee<'info>,
+    /// Instructions sysvar, required when the campaign restricts CPI callers
+    pub instructions_sysvar: Option<&'info AccountInfo>,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for ClaimZkAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, vault, zk_config, payer, destination, nullifier, system_program, config, remaining @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let (fee, remaining) = ProtocolFee::parse(config, remaining)?;
+
+        let instructions_sysvar = match remaining {
+            [] => None,
+            [instructions_sysvar] => {
+                check_instructions_sysvar(instructions_sysvar)?;
+                Some(instructions_sysvar)
+            }
+            _ => return Err(ProgramError::InvalidArgument),
+        };
+
+        check_airdrop_state(airdrop_state, true)?;
+        check_vault(vault)?;
+
+        check_program_owned(zk_config)?;
+        check_initialized(zk_config)?;
+
+        check_signer(payer)?;
+        check_writable(payer)?;
+        check_writable(destination)?;
+
+        check_writable(nullifier)?;
+        if !nullifier.data_is_empty() {
+            return Err(AirdropProgramError::AccountAlreadyClaimed.into());
+        }
+
+        check_system_program(system_program)?;
+
+        Ok(ClaimZkAccounts {
+            airdrop_state,
+            vault,
+            zk_config,
+            payer,
+            destination,
+            nullifier,
+            fee,
+            instructions_sysvar,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct ClaimZkInstructionData {
+    pub nullifier_hash: [u8; 32],
+    pub proof_a: [u8; 64],
+    pub proof_b: [u8; 128],
+    pub proof_c: [u8; 64],
+    pub tier: u8,
+    pub bump: u8,
+}
+
+impl DataLen for ClaimZkInstructionData {
+    const LEN: usize = core::mem::size_of::<ClaimZkInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for ClaimZkInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for ClaimZkInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Public inputs of the claim circuit, in the order of the verifying key's
+/// `ic` points. The destination is split in two halves to fit the field.
+pub fn zk_public_inputs(
+    merkle_root: &[u8; 32],
+    nullifier_hash: &[u8; 32],
+    tier: u8,
+    destination: &Pubkey,
+) -> [[u8; 32]; ZK_PUBLIC_INPUTS] {
+    [
+        *merkle_root,
+        *nullifier_hash,
+        zk_field_element(&[tier]),
+        zk_field_element(&destination[..16]),
+        zk_field_element(&destination[16..]),
+    ]
+}
+
+/// Anonymous claim: a Groth16 proof shows the claimer knows a commitment in
+/// the tree and derives the nullifier from it, without revealing which leaf.
+/// The nullifier PDA prevents double claims and the amount comes from the
+/// tier in `ZkConfig`.
+pub struct ClaimZk<'info> {
+    pub accounts: ClaimZkAccounts<'info>,
+    pub instruction_data: ClaimZkInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for ClaimZk<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = ClaimZkAccounts::try_from(accounts)?;
+        let instruction_data = ClaimZkInstructionData::try_from(data)?;
+
+        Ok(ClaimZk {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> ClaimZk<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &9;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let nullifier_hash = self.instruction_data.nullifier_hash;
+        let tier = self.instruction_data.tier;
+
+        let merkle_root = {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+            if airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+            check_cpi_policy(&airdrop_state, self.accounts.instructions_sysvar)?;
+            airdrop_state
+                .validate_vault(self.accounts.vault.key(), self.accounts.airdrop_state.key())?;
+            airdrop_state.merkle_root
+        };
+
+        // Verify the proof against the campaign's verifying key
+        let amount = {
+            let zk_config = ZkConfig::load(self.accounts.zk_config)?;
+            ZkConfig::validate_pda(
+                self.accounts.zk_config.key(),
+                self.accounts.airdrop_state.key(),
+                zk_config.bump[0],
+            )?;
+            let amount = zk_config
+                .tier_amount(tier)
+                .ok_or(ProgramError::InvalidInstructionData)?;
+
+            let public_inputs = zk_public_inputs(
+                &merkle_root,
+                &nullifier_hash,
+                tier,
+                self.accounts.destination.key(),
+            );
+            if !verify_groth16(
+                &zk_config.vk_alpha_g1,
+                &zk_config.vk_beta_g2,
+                &zk_config.vk_gamma_g2,
+                &zk_config.vk_delta_g2,
+                &zk_config.vk_ic,
+                &self.instruction_data.proof_a,
+                &self.instruction_data.proof_b,
+                &self.instruction_data.proof_c,
+                &public_inputs,
+            ) {
+                return Err(AirdropProgramError::InvalidProof.into());
+            }
+            amount
+        };
+
+        Nullifier::validate_pda(
+            self.accounts.nullifier.key(),
+            self.accounts.airdrop_state.key(),
+            &nullifier_hash,
+            self.instruction_data.bump,
+        )?;
+
+        // init the nullifier to avoid double claims
+        {
+            let bump_binding = [self.instruction_data.bump];
+            let seed = [
+                Seed::from(Nullifier::SEED),
+                Seed::from(self.accounts.airdrop_state.key().as_ref()),
+                Seed::from(nullifier_hash.as_ref()),
+                Seed::from(&bump_binding),
+            ];
+            let signer_seeds = Signer::from(&seed);
+
+            pinocchio_system::instructions::CreateAccount {
+                from: self.accounts.payer,
+                to: self.accounts.nullifier,
+                space: Nullifier::LEN as u64,
+                lamports: Rent::get()?.minimum_balance(Nullifier::LEN),
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[signer_seeds])?;
+
+            let mut nullifier = Nullifier::init_mut(self.accounts.nullifier)?;
+
+            nullifier.bump = [self.instruction_data.bump];
+        }
+
+        let fee = self.accounts.fee.fee_for(amount)?;
+        self.accounts.fee.pay_out(
+            self.accounts.vault,
+            self.accounts.payer,
+            self.accounts.destination,
+            amount,
+            fee,
+        )?;
+
+        {
+            let mut airdrop_state = AirdropState::load_mut(self.accounts.airdrop_state)?;
+            let amount_claimed = airdrop_state.amount_claimed.get();
+            airdrop_state
+                .amount_claimed
+                .set(amount_claimed.saturating_add(amount));
+        }
+
+        ClaimEvent::new(
+            self.accounts.airdrop_state.key(),
+            self.accounts.destination.key(),
+            amount,
+            ClaimEvent::NO_LEAF_INDEX,
+            fee,
+        )
+        .emit();
+
+        Ok(())
+    }
+}
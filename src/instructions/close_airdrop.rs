@@ -0,0 +1,97 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::AirdropState,
+    utils::AccountHeader,
+    validation::{check_airdrop_state, check_signer, check_vault, check_writable},
+};
+
+pub struct CloseAirdropAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub vault: &'info AccountInfo,
+    pub authority: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for CloseAirdropAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, vault, authority] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, true)?;
+        check_vault(vault)?;
+
+        check_signer(authority)?;
+        check_writable(authority)?;
+
+        Ok(CloseAirdropAccounts {
+            airdrop_state,
+            vault,
+            authority,
+        })
+    }
+}
+
+/// Claws back the unclaimed balance of the vault to the authority and marks
+/// the campaign closed. The state account and the vault are kept (rent-exempt)
+/// so the PDAs can never be re-initialized and claim-status accounts can be
+/// safely closed afterwards.
+pub struct CloseAirdrop<'info> {
+    pub accounts: CloseAirdropAccounts<'info>,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for CloseAirdrop<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let accounts = CloseAirdropAccounts::try_from(accounts)?;
+
+        Ok(CloseAirdrop { accounts })
+    }
+}
+
+impl<'info> CloseAirdrop<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        {
+            let mut airdrop_state = AirdropState::load_mut(self.accounts.airdrop_state)?;
+
+            if self.accounts.authority.key().ne(&airdrop_state.authority) {
+                return Err(AirdropProgramError::Unauthorized.into());
+            }
+            if airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+            airdrop_state
+                .validate_vault(self.accounts.vault.key(), self.accounts.airdrop_state.key())?;
+
+            airdrop_state.closed = [1];
+        }
+
+        {
+            // return everything above the vault's rent to the authority
+            let rent_exempt = Rent::get()?.minimum_balance(0);
+            let unclaimed = self.accounts.vault.lamports().saturating_sub(rent_exempt);
+
+            *self.accounts.vault.try_borrow_mut_lamports()? -= unclaimed;
+            *self.accounts.authority.try_borrow_mut_lamports()? += unclaimed;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::{AirdropState, ClaimStatus, ClaimStatusV0, LeafClaimStatus},
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{check_airdrop_state, check_program_owned, check_signer, check_writable},
+};
+
+#[cfg(feature = "zk")]
+use crate::states::Nullifier;
+
+pub struct CloseClaimStatusAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub signer: &'info AccountInfo,
+    pub user_claim: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for CloseClaimStatusAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, signer, user_claim] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, false)?;
+
+        check_signer(signer)?;
+        check_writable(signer)?;
+
+        check_program_owned(user_claim)?;
+        check_writable(user_claim)?;
+
+        Ok(CloseClaimStatusAccounts {
+            airdrop_state,
+            signer,
+            user_claim,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct CloseClaimStatusInstructionData {
+    /// The claimer the claim-status PDA was derived for
+    pub claimer: Pubkey,
+}
+
+impl DataLen for CloseClaimStatusInstructionData {
+    const LEN: usize = core::mem::size_of::<CloseClaimStatusInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for CloseClaimStatusInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for CloseClaimStatusInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Which claim status to close, told apart by the instruction data length:
+/// a 32-byte claimer, an 8-byte little-endian leaf index or the 20-byte EVM
+/// address of an `evm_claim` status. A 32-byte key is the nullifier hash when
+/// the account holds a `Nullifier`. A claimer-keyed status may still have the
+/// headerless `ClaimStatusV0` layout.
+pub enum ClaimStatusKey {
+    Claimer(Pubkey),
+    LeafIndex(u64),
+    EvmAddress([u8; 20]),
+    #[cfg(feature = "zk")]
+    Nullifier([u8; 32]),
+}
+
+/// Closes a claim-status PDA of a closed campaign and refunds its rent to the
+/// signer. The signer is either the recorded rent payer (the claimer for
+/// statuses of the first deployment) or, in reclaim mode, the campaign
+/// authority. Nullifiers do not record who paid for them, so only the
+/// authority may close those.
+pub struct CloseClaimStatus<'info> {
+    pub accounts: CloseClaimStatusAccounts<'info>,
+    pub key: ClaimStatusKey,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for CloseClaimStatus<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = CloseClaimStatusAccounts::try_from(accounts)?;
+        let key = match data.len() {
+            CloseClaimStatusInstructionData::LEN => {
+                let key = CloseClaimStatusInstructionData::try_from(data)?.claimer;
+                #[cfg(feature = "zk")]
+                if accounts
+                    .user_claim
+                    .try_borrow_data()?
+                    .starts_with(&Nullifier::DISCRIMINATOR)
+                {
+                    return Ok(CloseClaimStatus {
+                        accounts,
+                        key: ClaimStatusKey::Nullifier(key),
+                    });
+                }
+                ClaimStatusKey::Claimer(key)
+            }
+            8 => ClaimStatusKey::LeafIndex(u64::from_le_bytes(
+                data.try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?,
+            )),
+            20 => ClaimStatusKey::EvmAddress(
+                data.try_into()
+                    .map_err(|_| ProgramError::InvalidInstructionData)?,
+            ),
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        Ok(CloseClaimStatus { accounts, key })
+    }
+}
+
+impl<'info> CloseClaimStatus<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let authority = {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+
+            // a claim status may only go away once no claim can ever be made again
+            if !airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropNotClosed.into());
+            }
+            airdrop_state.authority
+        };
+
+        // the key proves which status this is and who may reclaim its rent
+        let owner = {
+            let data = self.accounts.user_claim.try_borrow_data()?;
+            match self.key {
+                ClaimStatusKey::Claimer(claimer) => {
+                    // a status of the first deployment holds only its bump;
+                    // back then the claimer always signed and paid
+                    if data.len() == ClaimStatusV0::LEN {
+                        let user_claim = ClaimStatusV0::ref_from(&data)
+                            .ok_or(ProgramError::InvalidAccountData)?;
+                        ClaimStatusV0::validate_pda(
+                            self.accounts.user_claim.key(),
+                            self.accounts.airdrop_state.key(),
+                            &claimer,
+                            user_claim.bump[0],
+                        )?;
+                        claimer
+                    } else {
+                        let user_claim = ClaimStatus::from_bytes(&data)?;
+                        ClaimStatus::validate_pda(
+                            self.accounts.user_claim.key(),
+                            self.accounts.airdrop_state.key(),
+                            &claimer,
+                            user_claim.bump[0],
+                        )?;
+                        user_claim.payer
+                    }
+                }
+                ClaimStatusKey::LeafIndex(leaf_index) => {
+                    let user_claim = LeafClaimStatus::from_bytes(&data)?;
+                    LeafClaimStatus::validate_pda(
+                        self.accounts.user_claim.key(),
+                        self.accounts.airdrop_state.key(),
+                        leaf_index,
+                        user_claim.bump[0],
+                    )?;
+                    user_claim.payer
+                }
+                ClaimStatusKey::EvmAddress(address) => {
+                    let user_claim = ClaimStatus::from_bytes(&data)?;
+                    ClaimStatus::validate_evm_pda(
+                        self.accounts.user_claim.key(),
+                        self.accounts.airdrop_state.key(),
+                        &address,
+                        user_claim.bump[0],
+                    )?;
+                    user_claim.payer
+                }
+                #[cfg(feature = "zk")]
+                ClaimStatusKey::Nullifier(nullifier_hash) => {
+                    let nullifier = Nullifier::from_bytes(&data)?;
+                    Nullifier::validate_pda(
+                        self.accounts.user_claim.key(),
+                        self.accounts.airdrop_state.key(),
+                        &nullifier_hash,
+                        nullifier.bump[0],
+                    )?;
+                    authority
+                }
+            }
+        };
+
+        let signer = self.accounts.signer.key();
+        if signer.ne(&owner) && signer.ne(&authority) {
+            return Err(AirdropProgramError::Unauthorized.into());
+        }
+
+        {
+            let rent = self.accounts.user_claim.lamports();
+            *self.accounts.user_claim.try_borrow_mut_lamports()? -= rent;
+            *self.accounts.signer.try_borrow_mut_lamports()? += rent;
+        }
+
+        self.accounts.user_claim.close()
+    }
+}
@@ -0,0 +1,84 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{
+    states::ProofBuffer,
+    validation::{check_airdrop_state, check_program_owned, check_signer, check_writable},
+};
+
+pub struct CloseProofBufferAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub claimer: &'info AccountInfo,
+    pub proof_buffer: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for CloseProofBufferAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, claimer, proof_buffer] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, false)?;
+
+        check_signer(claimer)?;
+        check_writable(claimer)?;
+
+        check_program_owned(proof_buffer)?;
+        check_writable(proof_buffer)?;
+
+        Ok(CloseProofBufferAccounts {
+            airdrop_state,
+            claimer,
+            proof_buffer,
+        })
+    }
+}
+
+/// Closes the claimer's proof buffer without claiming and refunds its rent,
+/// for a buffer a claim never consumed: a wrong proof, a root update or a
+/// closed campaign. Only the claimer the buffer is derived for may close it.
+pub struct CloseProofBuffer<'info> {
+    pub accounts: CloseProofBufferAccounts<'info>,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for CloseProofBuffer<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let accounts = CloseProofBufferAccounts::try_from(accounts)?;
+
+        Ok(CloseProofBuffer { accounts })
+    }
+}
+
+impl<'info> CloseProofBuffer<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &20;
+
+    pub fn process(&mut self) -> ProgramResult {
+        {
+            let data = self.accounts.proof_buffer.try_borrow_data()?;
+            let (header, _) = ProofBuffer::unpack(&data)?;
+            ProofBuffer::validate_pda(
+                self.accounts.proof_buffer.key(),
+                self.accounts.airdrop_state.key(),
+                self.accounts.claimer.key(),
+                header.bump[0],
+            )?;
+        }
+
+        {
+            let rent = self.accounts.proof_buffer.lamports();
+            *self.accounts.proof_buffer.try_borrow_mut_lamports()? -= rent;
+            *self.accounts.claimer.try_borrow_mut_lamports()? += rent;
+        }
+
+        self.accounts.proof_buffer.close()
+    }
+}
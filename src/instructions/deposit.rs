@@ -0,0 +1,114 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::AirdropState,
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{
+        check_airdrop_state, check_signer, check_system_program, check_vault, check_writable,
+    },
+};
+
+pub struct DepositAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub vault: &'info AccountInfo,
+    pub depositor: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for DepositAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, vault, depositor, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, false)?;
+        check_vault(vault)?;
+
+        check_signer(depositor)?;
+        check_writable(depositor)?;
+
+        check_system_program(system_program)?;
+
+        Ok(DepositAccounts {
+            airdrop_state,
+            vault,
+            depositor,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct DepositInstructionData {
+    pub amount: u64,
+}
+
+impl DataLen for DepositInstructionData {
+    const LEN: usize = core::mem::size_of::<DepositInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for DepositInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for DepositInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Tops up the vault of an open campaign. Anyone may deposit; the root and
+/// `airdrop_amount` are left untouched, so a deposit beyond what the tree
+/// owes can be taken back by the authority with `WithdrawExcess`.
+pub struct Deposit<'info> {
+    pub accounts: DepositAccounts<'info>,
+    pub instruction_data: DepositInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for Deposit<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = DepositAccounts::try_from(accounts)?;
+        let instruction_data = DepositInstructionData::try_from(data)?;
+
+        Ok(Deposit {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> Deposit<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &13;
+
+    pub fn process(&mut self) -> ProgramResult {
+        {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+            if airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+            airdrop_state
+                .validate_vault(self.accounts.vault.key(), self.accounts.airdrop_state.key())?;
+        }
+
+        if self.instruction_data.amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        pinocchio_system::instructions::Transfer {
+            from: self.accounts.depositor,
+            to: self.accounts.vault,
+            lamports: self.instruction_data.amount,
+        }
+        .invoke()
+    }
+}
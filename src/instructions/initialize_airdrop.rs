@@ -1,42 +1,63 @@
-use core::mem::transmute;
-
 use pinocchio::{
     account_info::AccountInfo,
     instruction::{Seed, Signer},
     program_error::ProgramError,
+    pubkey::Pubkey,
     sysvars::{rent::Rent, Sysvar},
     ProgramResult,
 };
 
 use crate::{
-    states::AirdropState,
-    utils::{load_acc_mut_unchecked, DataLen},
+    errors::AirdropProgramError,
+    instructions::SetMetadataInstructionData,
+    states::{AirdropState, Vault},
+    utils::{AccountHeader, DataLen, HashFunction, ZeroCopy},
+    validation::{check_signer, check_system_program, check_uninitialized, check_writable},
 };
 
 pub struct InitializeAirdropAccounts<'info> {
     pub airdrop_state: &'info AccountInfo,
+    pub vault: &'info AccountInfo,
     pub authority: &'info AccountInfo,
+    /// Optional metadata PDA, created along with the campaign
+    pub metadata: Option<&'info AccountInfo>,
 }
 
 impl<'info> TryFrom<&'info [AccountInfo]> for InitializeAirdropAccounts<'info> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
-        let [airdrop_state, authority, _] = accounts else {
+        let [airdrop_state, vault, authority, system_program, remaining @ ..] = accounts else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        // verify airdrop_state
-        if !airdrop_state.is_writable() {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if !airdrop_state.data_is_empty() {
-            return Err(ProgramError::AccountAlreadyInitialized);
-        }
+        let metadata = match remaining {
+            [] => None,
+            [metadata] => {
+                check_writable(metadata)?;
+                check_uninitialized(metadata)?;
+                Some(metadata)
+            }
+            _ => return Err(ProgramError::InvalidArgument),
+        };
+
+        check_writable(airdrop_state)?;
+        check_uninitialized(airdrop_state)?;
+
+        // may already hold lamports, see `Vault::create`
+        check_writable(vault)?;
+        check_uninitialized(vault)?;
+
+        check_signer(authority)?;
+        check_writable(authority)?;
+
+        check_system_program(system_program)?;
 
         Ok(InitializeAirdropAccounts {
             airdrop_state,
+            vault,
             authority,
+            metadata,
         })
     }
 }
@@ -46,32 +67,45 @@ pub struct InitializeAirdropInstructionData {
     pub merkle_root: [u8; 32],
     pub amount: u64,
     pub bump: u8,
+    /// Who may push claims when `distribution_mode` is `MODE_OPERATOR`
+    pub operator: Pubkey,
+    pub distribution_mode: u8,
+    /// `HashFunction` the merkle root was built with
+    pub hash_fn: u8,
+    /// `CLAIM_KEY_CLAIMER` or `CLAIM_KEY_LEAF_INDEX`
+    pub claim_key: u8,
+    /// Bump seed of the `Vault` PDA
+    pub vault_bump: u8,
 }
 
 impl DataLen for InitializeAirdropInstructionData {
     const LEN: usize = core::mem::size_of::<InitializeAirdropInstructionData>();
 }
 
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for InitializeAirdropInstructionData {}
+
 impl<'info> TryFrom<&'info [u8]> for InitializeAirdropInstructionData {
     type Error = ProgramError;
 
     fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
-        if data.len() != Self::LEN {
+        if data.len() < Self::LEN {
             return Err(ProgramError::InvalidInstructionData);
         }
 
-        Ok(unsafe {
-            transmute(
-                TryInto::<[u8; Self::LEN]>::try_into(data)
-                    .map_err(|_| ProgramError::InvalidInstructionData)?,
-            )
-        })
+        let fixed_data = &data[..Self::LEN];
+
+        Self::read_from(fixed_data).ok_or(ProgramError::InvalidInstructionData)
     }
 }
 
+/// Creates the campaign and its vault. When a metadata account follows the
+/// system program, the `SetMetadata` data follows the fixed data and the
+/// metadata is created in the same instruction.
 pub struct InitializeAirdrop<'info> {
     pub accounts: InitializeAirdropAccounts<'info>,
     pub instruction_data: InitializeAirdropInstructionData,
+    pub metadata: Option<SetMetadataInstructionData>,
 }
 
 impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for InitializeAirdrop<'info> {
@@ -82,22 +116,44 @@ impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for InitializeAirdrop<'
     ) -> Result<Self, Self::Error> {
         let accounts = InitializeAirdropAccounts::try_from(accounts)?;
         let instruction_data = InitializeAirdropInstructionData::try_from(data)?;
+        let metadata_data = &data[InitializeAirdropInstructionData::LEN..];
+        let metadata = match accounts.metadata {
+            Some(_) => Some(SetMetadataInstructionData::try_from(metadata_data)?),
+            None if metadata_data.is_empty() => None,
+            None => return Err(ProgramError::InvalidInstructionData),
+        };
+
+        if instruction_data.distribution_mode > AirdropState::MODE_CLAIM_AUTHORITY {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        HashFunction::try_from(instruction_data.hash_fn)?;
+        if instruction_data.claim_key > AirdropState::CLAIM_KEY_LEAF_INDEX {
+            return Err(ProgramError::InvalidInstructionData);
+        }
 
         Ok(InitializeAirdrop {
             accounts,
             instruction_data,
+            metadata,
         })
     }
 }
 
 impl<'info> InitializeAirdrop<'info> {
     pub const DISCRIMINATOR: &'info u8 = &0;
+    /// `sha256("global:initialize_airdrop")[..8]`
+    pub const ANCHOR_DISCRIMINATOR: [u8; 8] = [96, 196, 74, 102, 61, 195, 48, 184];
 
     pub fn process(&mut self) -> ProgramResult {
         AirdropState::validate_pda(
             self.accounts.airdrop_state.key(),
             self.instruction_data.bump,
         )?;
+        Vault::validate_pda(
+            self.accounts.vault.key(),
+            self.accounts.airdrop_state.key(),
+            self.instruction_data.vault_bump,
+        )?;
 
         {
             // create and init airdrop state account
@@ -114,26 +170,43 @@ impl<'info> InitializeAirdrop<'info> {
             }
             .invoke_signed(&[signer_seeds])?;
 
-            let mut data: pinocchio::account_info::RefMut<'_, [u8]> =
-                self.accounts.airdrop_state.try_borrow_mut_data()?;
-            let airdrop_state = unsafe { load_acc_mut_unchecked::<AirdropState>(&mut data) }?;
+            let mut airdrop_state = AirdropState::init_mut(self.accounts.airdrop_state)?;
 
             airdrop_state.merkle_root = self.instruction_data.merkle_root;
             airdrop_state.authority = *self.accounts.authority.key();
             airdrop_state.bump = [self.instruction_data.bump];
-            airdrop_state.airdrop_amount = self.instruction_data.amount.to_le_bytes();
-            airdrop_state.amount_claimed = 0u64.to_le_bytes();
+            airdrop_state.airdrop_amount = self.instruction_data.amount.into();
+            airdrop_state.amount_claimed = 0u64.into();
+            airdrop_state.closed = [0];
+            airdrop_state.operator = self.instruction_data.operator;
+            airdrop_state.distribution_mode = [self.instruction_data.distribution_mode];
+            airdrop_state.hash_fn = [self.instruction_data.hash_fn];
+            airdrop_state.claim_key = [self.instruction_data.claim_key];
+            airdrop_state.vault_bump = [self.instruction_data.vault_bump];
+            airdrop_state.cpi_policy = [AirdropState::CPI_ALLOW_ALL];
+            airdrop_state.cpi_caller_count = [0];
         }
 
-        {
-            // transfer sol to airdrop_state
-            pinocchio_system::instructions::Transfer {
-                from: self.accounts.authority,
-                to: self.accounts.airdrop_state,
-                lamports: self.instruction_data.amount,
-            }
-            .invoke()?;
+        // create the vault funded with the pool on top of its rent
+        Vault::create(
+            self.accounts.authority,
+            self.accounts.vault,
+            self.accounts.airdrop_state.key(),
+            self.instruction_data.vault_bump,
+            Rent::get()?
+                .minimum_balance(0)
+                .checked_add(self.instruction_data.amount)
+                .ok_or(AirdropProgramError::ArithmeticOverflow)?,
+        )?;
+
+        if let (Some(metadata), Some(metadata_data)) = (self.accounts.metadata, &self.metadata) {
+            metadata_data.write(
+                self.accounts.authority,
+                metadata,
+                self.accounts.airdrop_state.key(),
+            )?;
         }
+
         Ok(())
     }
 }
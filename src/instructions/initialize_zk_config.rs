@@ -0,0 +1,166 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::{AirdropState, ZkConfig, MAX_ZK_TIERS},
+    utils::{AccountHeader, DataLen, ZeroCopy, ZK_PUBLIC_INPUTS},
+    validation::{
+        check_airdrop_state, check_signer, check_system_program, check_uninitialized,
+        check_writable,
+    },
+};
+
+pub struct InitializeZkConfigAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub authority: &'info AccountInfo,
+    pub zk_config: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for InitializeZkConfigAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, authority, zk_config, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, false)?;
+
+        check_signer(authority)?;
+        check_writable(authority)?;
+
+        check_writable(zk_config)?;
+        check_uninitialized(zk_config)?;
+
+        check_system_program(system_program)?;
+
+        Ok(InitializeZkConfigAccounts {
+            airdrop_state,
+            authority,
+            zk_config,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct InitializeZkConfigInstructionData {
+    pub bump: u8,
+    pub tier_count: u8,
+    pub tier_amounts: [u64; MAX_ZK_TIERS],
+    pub vk_alpha_g1: [u8; 64],
+    pub vk_beta_g2: [u8; 128],
+    pub vk_gamma_g2: [u8; 128],
+    pub vk_delta_g2: [u8; 128],
+    pub vk_ic: [[u8; 64]; ZK_PUBLIC_INPUTS + 1],
+}
+
+impl DataLen for InitializeZkConfigInstructionData {
+    const LEN: usize = core::mem::size_of::<InitializeZkConfigInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for InitializeZkConfigInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for InitializeZkConfigInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Turns a campaign into an anonymous one: stores the Groth16 verifying key
+/// of the claim circuit and the fixed amount of every tier. The key cannot be
+/// replaced afterwards, so a claim can never be re-proved under another key.
+pub struct InitializeZkConfig<'info> {
+    pub accounts: InitializeZkConfigAccounts<'info>,
+    pub instruction_data: InitializeZkConfigInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for InitializeZkConfig<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = InitializeZkConfigAccounts::try_from(accounts)?;
+        let instruction_data = InitializeZkConfigInstructionData::try_from(data)?;
+
+        let tier_count = instruction_data.tier_count as usize;
+        if tier_count == 0 || tier_count > MAX_ZK_TIERS {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(InitializeZkConfig {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> InitializeZkConfig<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &8;
+
+    pub fn process(&mut self) -> ProgramResult {
+        {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+
+            if self.accounts.authority.key().ne(&airdrop_state.authority) {
+                return Err(AirdropProgramError::Unauthorized.into());
+            }
+            if airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+        }
+
+        let bump = self.instruction_data.bump;
+        ZkConfig::validate_pda(
+            self.accounts.zk_config.key(),
+            self.accounts.airdrop_state.key(),
+            bump,
+        )?;
+
+        let bump_binding = [bump];
+        let seed = [
+            Seed::from(ZkConfig::SEED),
+            Seed::from(self.accounts.airdrop_state.key().as_ref()),
+            Seed::from(&bump_binding),
+        ];
+        let signer_seeds = Signer::from(&seed);
+
+        pinocchio_system::instructions::CreateAccount {
+            from: self.accounts.authority,
+            to: self.accounts.zk_config,
+            space: ZkConfig::LEN as u64,
+            lamports: Rent::get()?.minimum_balance(ZkConfig::LEN),
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[signer_seeds])?;
+
+        let mut zk_config = ZkConfig::init_mut(self.accounts.zk_config)?;
+
+        let tier_amounts = self.instruction_data.tier_amounts;
+        zk_config.bump = [bump];
+        zk_config.tier_count = [self.instruction_data.tier_count];
+        for (stored, amount) in zk_config.tier_amounts.iter_mut().zip(tier_amounts) {
+            *stored = amount.into();
+        }
+        zk_config.vk_alpha_g1 = self.instruction_data.vk_alpha_g1;
+        zk_config.vk_beta_g2 = self.instruction_data.vk_beta_g2;
+        zk_config.vk_gamma_g2 = self.instruction_data.vk_gamma_g2;
+        zk_config.vk_delta_g2 = self.instruction_data.vk_delta_g2;
+        zk_config.vk_ic = self.instruction_data.vk_ic;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::AirdropState,
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{check_airdrop_state, check_signer},
+};
+
+pub struct MigrateClaimKeyAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub authority: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for MigrateClaimKeyAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, authority] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, true)?;
+
+        check_signer(authority)?;
+
+        Ok(MigrateClaimKeyAccounts {
+            airdrop_state,
+            authority,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct MigrateClaimKeyInstructionData {
+    /// Root of the leaves not yet claimed under the claimer-keyed statuses
+    pub new_merkle_root: [u8; 32],
+}
+
+impl DataLen for MigrateClaimKeyInstructionData {
+    const LEN: usize = core::mem::size_of::<MigrateClaimKeyInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for MigrateClaimKeyInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for MigrateClaimKeyInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Moves a campaign from claimer-keyed to leaf-keyed claim statuses.
+///
+/// Leaf-keyed statuses know nothing of the claims already made, so the
+/// switch comes with a new root that leaves out every leaf claimed so far
+/// (the claimer-keyed statuses can be listed off-chain). A claim can still
+/// land between that snapshot and this instruction, so the campaign moves to
+/// `CLAIM_KEY_MIGRATED`: every later claim also requires the claimer-keyed
+/// status of its leaf to be empty.
+pub struct MigrateClaimKey<'info> {
+    pub accounts: MigrateClaimKeyAccounts<'info>,
+    pub instruction_data: MigrateClaimKeyInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for MigrateClaimKey<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = MigrateClaimKeyAccounts::try_from(accounts)?;
+        let instruction_data = MigrateClaimKeyInstructionData::try_from(data)?;
+
+        Ok(MigrateClaimKey {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> MigrateClaimKey<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &10;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut airdrop_state = AirdropState::load_mut(self.accounts.airdrop_state)?;
+
+        if self.accounts.authority.key().ne(&airdrop_state.authority) {
+            return Err(AirdropProgramError::Unauthorized.into());
+        }
+        if airdrop_state.is_closed() {
+            return Err(AirdropProgramError::AirdropClosed.into());
+        }
+        if airdrop_state.is_keyed_by_leaf() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        airdrop_state.merkle_root = self.instruction_data.new_merkle_root;
+        airdrop_state.claim_key = [AirdropState::CLAIM_KEY_MIGRATED];
+
+        Ok(())
+    }
+}
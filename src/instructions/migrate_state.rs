@@ -0,0 +1,246 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::{AirdropState, AirdropStateV0, AirdropStateV1, Vault},
+    utils::{AccountHeader, DataLen, HashFunction, ZeroCopy},
+    validation::{
+        check_program_owned, check_signer, check_system_program, check_uninitialized,
+        check_writable,
+    },
+};
+
+pub struct MigrateStateAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    /// Created when migrating from version 0, which kept the funds in the
+    /// state account
+    pub vault: &'info AccountInfo,
+    pub authority: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for MigrateStateAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, vault, authority, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // the layout is not known yet: `check_airdrop_state` would reject
+        // any account that is not already current
+        check_program_owned(airdrop_state)?;
+        check_writable(airdrop_state)?;
+
+        check_writable(vault)?;
+
+        check_signer(authority)?;
+        check_writable(authority)?;
+
+        check_system_program(system_program)?;
+
+        Ok(MigrateStateAccounts {
+            airdrop_state,
+            vault,
+            authority,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct MigrateStateInstructionData {
+    /// Bump seed of the `Vault` PDA, used when migrating from version 0
+    pub vault_bump: u8,
+}
+
+impl DataLen for MigrateStateInstructionData {
+    const LEN: usize = core::mem::size_of::<MigrateStateInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for MigrateStateInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for MigrateStateInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Upgrades a campaign account written by an older program to the current
+/// `AirdropState` layout: reallocs it to `AirdropState::LEN`, tops up its rent
+/// from the authority and converts its fields by layout version. An account
+/// that is already current is left untouched.
+pub struct MigrateState<'info> {
+    pub accounts: MigrateStateAccounts<'info>,
+    pub instruction_data: MigrateStateInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for MigrateState<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = MigrateStateAccounts::try_from(accounts)?;
+        let instruction_data = MigrateStateInstructionData::try_from(data)?;
+
+        Ok(MigrateState {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> MigrateState<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &17;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let version = {
+            let data = self.accounts.airdrop_state.try_borrow_data()?;
+            AirdropState::layout_version(&data)?
+        };
+
+        match version {
+            0 => self.migrate_v0(),
+            1 => self.migrate_v1(),
+            AirdropState::VERSION => {
+                let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+                if self.accounts.authority.key().ne(&airdrop_state.authority) {
+                    return Err(AirdropProgramError::Unauthorized.into());
+                }
+                Ok(())
+            }
+            _ => Err(AirdropProgramError::UnsupportedAccountVersion.into()),
+        }
+    }
+
+    /// Version 0 kept the funds in the state account, so they move to a new
+    /// vault. Its `amount_claimed` cannot be trusted: `ClaimAirdrop` wrote it
+    /// big-endian and read it back little-endian, so after a second claim it
+    /// no longer matches any sum. It is rebuilt from the balance instead.
+    fn migrate_v0(&mut self) -> ProgramResult {
+        let (merkle_root, authority, airdrop_amount, bump) = {
+            let data = self.accounts.airdrop_state.try_borrow_data()?;
+            let v0 = AirdropStateV0::ref_from(&data).ok_or(ProgramError::InvalidAccountData)?;
+            (
+                v0.merkle_root,
+                v0.authority,
+                u64::from_le_bytes(v0.airdrop_amount),
+                v0.bump[0],
+            )
+        };
+
+        AirdropState::validate_pda(self.accounts.airdrop_state.key(), bump)?;
+        if self.accounts.authority.key().ne(&authority) {
+            return Err(AirdropProgramError::Unauthorized.into());
+        }
+
+        let vault_bump = self.instruction_data.vault_bump;
+        Vault::validate_pda(
+            self.accounts.vault.key(),
+            self.accounts.airdrop_state.key(),
+            vault_bump,
+        )?;
+        check_uninitialized(self.accounts.vault)?;
+
+        let rent = Rent::get()?;
+
+        // everything above the old rent is what recipients have not claimed
+        let held = self
+            .accounts
+            .airdrop_state
+            .lamports()
+            .saturating_sub(rent.minimum_balance(AirdropStateV0::LEN));
+        let amount_claimed = airdrop_amount.saturating_sub(held);
+
+        Vault::create(
+            self.accounts.authority,
+            self.accounts.vault,
+            self.accounts.airdrop_state.key(),
+            vault_bump,
+            rent.minimum_balance(0),
+        )?;
+
+        {
+            *self.accounts.airdrop_state.try_borrow_mut_lamports()? -= held;
+            *self.accounts.vault.try_borrow_mut_lamports()? += held;
+        }
+
+        self.grow(&rent)?;
+
+        let mut data = self.accounts.airdrop_state.try_borrow_mut_data()?;
+        data.fill(0);
+        let airdrop_state = AirdropState::init_bytes(&mut data)?;
+
+        airdrop_state.merkle_root = merkle_root;
+        airdrop_state.authority = authority;
+        airdrop_state.airdrop_amount = airdrop_amount.into();
+        airdrop_state.amount_claimed = amount_claimed.into();
+        airdrop_state.bump = [bump];
+        airdrop_state.closed = [0];
+        airdrop_state.operator = Pubkey::default();
+        airdrop_state.distribution_mode = [AirdropState::MODE_SELF_CLAIM];
+        airdrop_state.hash_fn = [HashFunction::Keccak256 as u8];
+        airdrop_state.claim_key = [AirdropState::CLAIM_KEY_CLAIMER];
+        airdrop_state.vault_bump = [vault_bump];
+        airdrop_state.cpi_policy = [AirdropState::CPI_ALLOW_ALL];
+        airdrop_state.cpi_caller_count = [0];
+
+        Ok(())
+    }
+
+    /// Version 1 is a prefix of version 2, so the account only grows: the
+    /// new CPI policy fields start zeroed, which allows every caller.
+    fn migrate_v1(&mut self) -> ProgramResult {
+        let (authority, bump) = {
+            let data = self.accounts.airdrop_state.try_borrow_data()?;
+            let v1 = AirdropStateV1::ref_from(&data).ok_or(ProgramError::InvalidAccountData)?;
+            (v1.authority, v1.bump[0])
+        };
+
+        AirdropState::validate_pda(self.accounts.airdrop_state.key(), bump)?;
+        if self.accounts.authority.key().ne(&authority) {
+            return Err(AirdropProgramError::Unauthorized.into());
+        }
+
+        self.grow(&Rent::get()?)?;
+
+        let mut data = self.accounts.airdrop_state.try_borrow_mut_data()?;
+        data[8] = AirdropState::VERSION;
+        let airdrop_state = AirdropState::from_bytes_mut(&mut data)?;
+
+        airdrop_state.cpi_policy = [AirdropState::CPI_ALLOW_ALL];
+        airdrop_state.cpi_caller_count = [0];
+
+        Ok(())
+    }
+
+    /// Top up the state account's rent for `AirdropState::LEN` from the
+    /// authority, then realloc it. New bytes are zeroed.
+    fn grow(&self, rent: &Rent) -> ProgramResult {
+        let top_up = rent
+            .minimum_balance(AirdropState::LEN)
+            .saturating_sub(self.accounts.airdrop_state.lamports());
+        if top_up > 0 {
+            pinocchio_system::instructions::Transfer {
+                from: self.accounts.authority,
+                to: self.accounts.airdrop_state,
+                lamports: top_up,
+            }
+            .invoke()?;
+        }
+
+        self.accounts.airdrop_state.resize(AirdropState::LEN)
+    }
+}
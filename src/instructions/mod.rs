@@ -6,3 +6,63 @@ pub use claim_airdrop::*;
 
 pub mod update_merkle_root;
 pub use update_merkle_root::*;
+
+pub mod close_airdrop;
+pub use close_airdrop::*;
+
+pub mod close_claim_status;
+pub use close_claim_status::*;
+
+pub mod claim_batch;
+pub use claim_batch::*;
+
+pub mod write_proof_buffer;
+pub use write_proof_buffer::*;
+
+pub mod migrate_claim_key;
+pub use migrate_claim_key::*;
+
+pub mod revoke_leaf;
+pub use revoke_leaf::*;
+
+pub mod reassign_leaf;
+pub use reassign_leaf::*;
+
+pub mod deposit;
+pub use deposit::*;
+
+pub mod withdraw_excess;
+pub use withdraw_excess::*;
+
+pub mod verify_claim;
+pub use verify_claim::*;
+
+pub mod set_metadata;
+pub use set_metadata::*;
+
+pub mod migrate_state;
+pub use migrate_state::*;
+
+pub mod set_cpi_policy;
+pub use set_cpi_policy::*;
+
+pub mod set_config;
+pub use set_config::*;
+
+pub mod close_proof_buffer;
+pub use close_proof_buffer::*;
+
+#[cfg(feature = "evm")]
+pub mod claim_evm;
+#[cfg(feature = "evm")]
+pub use claim_evm::*;
+
+#[cfg(feature = "zk")]
+pub mod initialize_zk_config;
+#[cfg(feature = "zk")]
+pub use initialize_zk_config::*;
+
+#[cfg(feature = "zk")]
+pub mod claim_zk;
+#[cfg(feature = "zk")]
+pub use claim_zk::*;
@@ -0,0 +1,174 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::{AirdropState, Redirect},
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{
+        check_airdrop_state, check_program_owned, check_signer, check_system_program,
+        check_writable,
+    },
+};
+
+pub struct ReassignLeafAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    /// Approves the rotation and pays the redirect rent
+    pub authority: &'info AccountInfo,
+    /// Recipient committed in the leaves; optionally co-signs, which the
+    /// redirect records
+    pub old_recipient: &'info AccountInfo,
+    pub redirect: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for ReassignLeafAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, authority, old_recipient, redirect, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, false)?;
+
+        check_signer(authority)?;
+        check_writable(authority)?;
+
+        check_writable(redirect)?;
+
+        check_system_program(system_program)?;
+
+        Ok(ReassignLeafAccounts {
+            airdrop_state,
+            authority,
+            old_recipient,
+            redirect,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct ReassignLeafInstructionData {
+    pub new_recipient: Pubkey,
+    pub bump: u8,
+}
+
+impl DataLen for ReassignLeafInstructionData {
+    const LEN: usize = core::mem::size_of::<ReassignLeafInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for ReassignLeafInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for ReassignLeafInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Records a redirect from a recipient that lost its key to a new pubkey. The
+/// authority always signs; the old key co-signs when it is still available,
+/// and the redirect records whether it did. Every claim passes the redirect
+/// PDA of its recipient, so once it exists only the new key can claim: it
+/// signs and receives the funds while the claim status stays keyed by the old
+/// one, so the leaf is still claimed only once. Calling it again on an
+/// existing redirect points it at another key.
+pub struct ReassignLeaf<'info> {
+    pub accounts: ReassignLeafAccounts<'info>,
+    pub instruction_data: ReassignLeafInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for ReassignLeaf<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = ReassignLeafAccounts::try_from(accounts)?;
+        let instruction_data = ReassignLeafInstructionData::try_from(data)?;
+
+        if instruction_data.new_recipient == *accounts.old_recipient.key() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(ReassignLeaf {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> ReassignLeaf<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &12;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let old_recipient = *self.accounts.old_recipient.key();
+        let bump = self.instruction_data.bump;
+
+        {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+
+            if self.accounts.authority.key().ne(&airdrop_state.authority) {
+                return Err(AirdropProgramError::Unauthorized.into());
+            }
+            if airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+        }
+
+        Redirect::validate_pda(
+            self.accounts.redirect.key(),
+            self.accounts.airdrop_state.key(),
+            &old_recipient,
+            bump,
+        )?;
+
+        let is_new = self.accounts.redirect.data_is_empty();
+        if is_new {
+            let bump_binding = [bump];
+            let seed = [
+                Seed::from(Redirect::SEED),
+                Seed::from(self.accounts.airdrop_state.key().as_ref()),
+                Seed::from(old_recipient.as_ref()),
+                Seed::from(&bump_binding),
+            ];
+            let signer_seeds = Signer::from(&seed);
+
+            pinocchio_system::instructions::CreateAccount {
+                from: self.accounts.authority,
+                to: self.accounts.redirect,
+                space: Redirect::LEN as u64,
+                lamports: Rent::get()?.minimum_balance(Redirect::LEN),
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[signer_seeds])?;
+        } else {
+            check_program_owned(self.accounts.redirect)?;
+        }
+
+        let mut redirect = if is_new {
+            Redirect::init_mut(self.accounts.redirect)?
+        } else {
+            Redirect::load_mut(self.accounts.redirect)?
+        };
+
+        redirect.bump = [bump];
+        redirect.old_recipient = old_recipient;
+        redirect.new_recipient = self.instruction_data.new_recipient;
+        redirect.signed_by_old_recipient = [self.accounts.old_recipient.is_signer() as u8];
+
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::{existing_claim_status_error, AirdropState, ClaimStatus, LeafClaimStatus, Vault},
+    utils::{
+        proof_from_bytes, verify_merkle_proof, AccountHeader, DataLen, HashFunction, ZeroCopy,
+        MAX_PROOF_LEN,
+    },
+    validation::{
+        check_airdrop_state, check_signer, check_system_program, check_vault, check_writable,
+    },
+};
+
+pub struct RevokeLeafAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub vault: &'info AccountInfo,
+    /// Pays the marker rent and receives the revoked amount
+    pub authority: &'info AccountInfo,
+    /// Claim status of the leaf, written as a revocation marker
+    pub user_claim: &'info AccountInfo,
+    /// Claim authority committed in the leaf, required in
+    /// `MODE_CLAIM_AUTHORITY`; does not need to sign
+    pub claim_authority: Option<&'info AccountInfo>,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for RevokeLeafAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, vault, authority, user_claim, system_program, remaining @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let claim_authority = match remaining {
+            [] => None,
+            [claim_authority] => Some(claim_authority),
+            _ => return Err(ProgramError::InvalidArgument),
+        };
+
+        check_airdrop_state(airdrop_state, true)?;
+        check_vault(vault)?;
+
+        check_signer(authority)?;
+        check_writable(authority)?;
+
+        check_writable(user_claim)?;
+        if !user_claim.data_is_empty() {
+            return Err(existing_claim_status_error(user_claim).into());
+        }
+
+        check_system_program(system_program)?;
+
+        Ok(RevokeLeafAccounts {
+            airdrop_state,
+            vault,
+            authority,
+            user_claim,
+            claim_authority,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct RevokeLeafInstructionData {
+    /// Recipient committed in the revoked leaf
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub leaf_index: u64,
+    /// Bump of the leaf's claim status
+    pub bump: u8,
+    pub proof_len: u8,
+}
+
+impl DataLen for RevokeLeafInstructionData {
+    const LEN: usize = core::mem::size_of::<RevokeLeafInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for RevokeLeafInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for RevokeLeafInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let fixed_data = &data[..Self::LEN];
+
+        Self::read_from(fixed_data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Blocks a single leaf without regenerating the tree, e.g. after sanctions
+/// screening or when the recipient key is compromised.
+///
+/// The leaf's claim status is created with the `revoked` flag, so a claim of
+/// the leaf fails with `LeafRevoked`. The proof makes the amount trustworthy:
+/// it is clawed back to the authority at once and leaves the allocation.
+pub struct RevokeLeaf<'info> {
+    pub accounts: RevokeLeafAccounts<'info>,
+    pub instruction_data: RevokeLeafInstructionData,
+    pub proof_data: &'info [[u8; 32]],
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for RevokeLeaf<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = RevokeLeafAccounts::try_from(accounts)?;
+        let instruction_data = RevokeLeafInstructionData::try_from(data)?;
+
+        // check data length
+        let proof_offset = RevokeLeafInstructionData::LEN;
+        let proof_len = instruction_data.proof_len as usize;
+        if proof_len > MAX_PROOF_LEN {
+            return Err(AirdropProgramError::ProofTooLong.into());
+        }
+        if data.len() != proof_offset + proof_len * 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let proof_data =
+            proof_from_bytes(&data[proof_offset..]).ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok(RevokeLeaf {
+            accounts,
+            instruction_data,
+            proof_data,
+        })
+    }
+}
+
+impl<'info> RevokeLeaf<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &11;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let recipient = self.instruction_data.recipient;
+        let amount = self.instruction_data.amount;
+        let leaf_index = self.instruction_data.leaf_index;
+        let bump = self.instruction_data.bump;
+
+        let keyed_by_leaf = {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+
+            if self.accounts.authority.key().ne(&airdrop_state.authority) {
+                return Err(AirdropProgramError::Unauthorized.into());
+            }
+            if airdrop_state.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+            airdrop_state
+                .validate_vault(self.accounts.vault.key(), self.accounts.airdrop_state.key())?;
+
+            // Verify merkle proof
+            let hash_fn = HashFunction::try_from(airdrop_state.hash_fn[0])?;
+            let leaf = if airdrop_state.uses_claim_authority() {
+                let claim_authority = self
+                    .accounts
+                    .claim_authority
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                hash_fn.create_delegated_airdrop_leaf(&recipient, claim_authority.key(), amount, 0)
+            } else {
+                hash_fn.create_airdrop_leaf(&recipient, amount, 0)
+            }
+            .ok_or(AirdropProgramError::InvalidProof)?;
+            if !verify_merkle_proof(
+                hash_fn,
+                &leaf,
+                self.proof_data,
+                leaf_index,
+                &airdrop_state.merkle_root,
+            ) {
+                return Err(AirdropProgramError::InvalidProof.into());
+            }
+
+            airdrop_state.is_keyed_by_leaf()
+        };
+
+        // write the revocation marker where the claim status would go
+        if keyed_by_leaf {
+            LeafClaimStatus::init(
+                self.accounts.authority,
+                self.accounts.user_claim,
+                self.accounts.airdrop_state.key(),
+                leaf_index,
+                bump,
+            )?;
+
+            let mut user_claim = LeafClaimStatus::load_mut(self.accounts.user_claim)?;
+            user_claim.revoked = [1];
+        } else {
+            ClaimStatus::init(
+                self.accounts.authority,
+                self.accounts.user_claim,
+                [
+                    ClaimStatus::SEED,
+                    self.accounts.airdrop_state.key().as_ref(),
+                    recipient.as_ref(),
+                ],
+                bump,
+            )?;
+
+            let mut user_claim = ClaimStatus::load_mut(self.accounts.user_claim)?;
+            user_claim.revoked = [1];
+        }
+
+        // claw back the revoked amount
+        {
+            Vault::debit(self.accounts.vault, amount)?;
+            *self.accounts.authority.try_borrow_mut_lamports()? += amount;
+        }
+
+        {
+            let mut airdrop_state = AirdropState::load_mut(self.accounts.airdrop_state)?;
+            let airdrop_amount = airdrop_state
+                .airdrop_amount
+                .get()
+                .checked_sub(amount)
+                .ok_or(AirdropProgramError::ArithmeticOverflow)?;
+            airdrop_state.airdrop_amount.set(airdrop_amount);
+        }
+
+        Ok(())
+    }
+}
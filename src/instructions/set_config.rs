@@ -0,0 +1,156 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::Config,
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{
+        check_config, check_signer, check_system_program, check_upgrade_authority, check_writable,
+    },
+};
+
+pub struct SetConfigAccounts<'info> {
+    pub config: &'info AccountInfo,
+    pub admin: &'info AccountInfo,
+    /// `ProgramData` of this program, only needed to create the config
+    pub program_data: Option<&'info AccountInfo>,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for SetConfigAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [config, admin, system_program, remaining @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_config(config)?;
+        check_writable(config)?;
+
+        check_signer(admin)?;
+        check_writable(admin)?;
+
+        check_system_program(system_program)?;
+
+        Ok(SetConfigAccounts {
+            config,
+            admin,
+            program_data: remaining.first(),
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct SetConfigInstructionData {
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    /// `FEE_NONE`, `FEE_BPS` or `FEE_FLAT`
+    pub fee_mode: u8,
+    pub fee: u64,
+    /// `FEE_FROM_AMOUNT` or `FEE_FROM_CLAIMER`
+    pub fee_source: u8,
+}
+
+impl DataLen for SetConfigInstructionData {
+    const LEN: usize = core::mem::size_of::<SetConfigInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for SetConfigInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for SetConfigInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Creates or rewrites the program-wide fee config. The upgrade authority of
+/// the program creates it, proven by the `ProgramData` account, and names the
+/// admin; after that only the admin may change it, including handing it over.
+pub struct SetConfig<'info> {
+    pub accounts: SetConfigAccounts<'info>,
+    pub instruction_data: SetConfigInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for SetConfig<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = SetConfigAccounts::try_from(accounts)?;
+        let instruction_data = SetConfigInstructionData::try_from(data)?;
+
+        if instruction_data.fee_mode > Config::FEE_FLAT
+            || instruction_data.fee_source > Config::FEE_FROM_CLAIMER
+            || (instruction_data.fee_mode == Config::FEE_BPS
+                && instruction_data.fee > Config::MAX_FEE_BPS)
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(SetConfig {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> SetConfig<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &19;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let is_new = self.accounts.config.data_is_empty();
+        if is_new {
+            let program_data = self
+                .accounts
+                .program_data
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            check_upgrade_authority(program_data, self.accounts.admin)?;
+
+            let bump_binding = [Config::BUMP];
+            let seed = [Seed::from(Config::SEED), Seed::from(&bump_binding)];
+            let signer_seeds = Signer::from(&seed);
+
+            pinocchio_system::instructions::CreateAccount {
+                from: self.accounts.admin,
+                to: self.accounts.config,
+                space: Config::LEN as u64,
+                lamports: Rent::get()?.minimum_balance(Config::LEN),
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[signer_seeds])?;
+        }
+
+        let mut config = if is_new {
+            Config::init_mut(self.accounts.config)?
+        } else {
+            let config = Config::load_mut(self.accounts.config)?;
+            if self.accounts.admin.key().ne(&config.admin) {
+                return Err(AirdropProgramError::Unauthorized.into());
+            }
+            config
+        };
+
+        config.admin = self.instruction_data.admin;
+        config.treasury = self.instruction_data.treasury;
+        config.fee_mode = [self.instruction_data.fee_mode];
+        config.fee = self.instruction_data.fee.into();
+        config.fee_source = [self.instruction_data.fee_source];
+
+        Ok(())
+    }
+}
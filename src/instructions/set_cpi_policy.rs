@@ -0,0 +1,111 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::AirdropState,
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{check_airdrop_state, check_signer},
+};
+
+pub struct SetCpiPolicyAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub authority: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for SetCpiPolicyAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, authority] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, true)?;
+
+        check_signer(authority)?;
+
+        Ok(SetCpiPolicyAccounts {
+            airdrop_state,
+            authority,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct SetCpiPolicyInstructionData {
+    /// `CPI_ALLOW_ALL`, `CPI_DENY` or `CPI_ALLOWLIST`
+    pub cpi_policy: u8,
+    /// Number of programs used in `cpi_callers`
+    pub cpi_caller_count: u8,
+    pub cpi_callers: [Pubkey; AirdropState::MAX_CPI_CALLERS],
+}
+
+impl DataLen for SetCpiPolicyInstructionData {
+    const LEN: usize = core::mem::size_of::<SetCpiPolicyInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for SetCpiPolicyInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for SetCpiPolicyInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Sets who may claim through CPI. Claims sent directly to the program are
+/// always accepted; with `CPI_DENY` nothing else is, and with `CPI_ALLOWLIST`
+/// only claims whose top-level instruction runs one of `cpi_callers`. Only the
+/// authority may call it.
+pub struct SetCpiPolicy<'info> {
+    pub accounts: SetCpiPolicyAccounts<'info>,
+    pub instruction_data: SetCpiPolicyInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for SetCpiPolicy<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = SetCpiPolicyAccounts::try_from(accounts)?;
+        let instruction_data = SetCpiPolicyInstructionData::try_from(data)?;
+
+        if instruction_data.cpi_policy > AirdropState::CPI_ALLOWLIST
+            || instruction_data.cpi_caller_count as usize > AirdropState::MAX_CPI_CALLERS
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(SetCpiPolicy {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> SetCpiPolicy<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &18;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut airdrop_state = AirdropState::load_mut(self.accounts.airdrop_state)?;
+
+        if self.accounts.authority.key().ne(&airdrop_state.authority) {
+            return Err(AirdropProgramError::Unauthorized.into());
+        }
+
+        airdrop_state.cpi_policy = [self.instruction_data.cpi_policy];
+        airdrop_state.cpi_caller_count = [self.instruction_data.cpi_caller_count];
+        airdrop_state.cpi_callers = self.instruction_data.cpi_callers;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::{AirdropState, Metadata},
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{
+        check_airdrop_state, check_program_owned, check_signer, check_system_program,
+        check_writable,
+    },
+};
+
+pub struct SetMetadataAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub authority: &'info AccountInfo,
+    pub metadata: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for SetMetadataAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, authority, metadata, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, false)?;
+
+        check_signer(authority)?;
+        check_writable(authority)?;
+
+        check_writable(metadata)?;
+
+        check_system_program(system_program)?;
+
+        Ok(SetMetadataAccounts {
+            airdrop_state,
+            authority,
+            metadata,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct SetMetadataInstructionData {
+    pub bump: u8,
+    pub name: [u8; Metadata::MAX_NAME_LEN],
+    pub uri: [u8; Metadata::MAX_URI_LEN],
+    pub tree_hash: [u8; 32],
+    pub leaf_count: u64,
+    pub depth: u8,
+}
+
+impl DataLen for SetMetadataInstructionData {
+    const LEN: usize = core::mem::size_of::<SetMetadataInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for SetMetadataInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for SetMetadataInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+impl SetMetadataInstructionData {
+    /// Create the metadata PDA of `airdrop` if needed, with `payer` paying
+    /// the rent, then overwrite every field.
+    pub fn write(
+        &self,
+        payer: &AccountInfo,
+        metadata: &AccountInfo,
+        airdrop: &Pubkey,
+    ) -> ProgramResult {
+        Metadata::validate_pda(metadata.key(), airdrop, self.bump)?;
+
+        let is_new = metadata.data_is_empty();
+        if is_new {
+            let bump_binding = [self.bump];
+            let seed = [
+                Seed::from(Metadata::SEED),
+                Seed::from(airdrop.as_ref()),
+                Seed::from(&bump_binding),
+            ];
+            let signer_seeds = Signer::from(&seed);
+
+            pinocchio_system::instructions::CreateAccount {
+                from: payer,
+                to: metadata,
+                space: Metadata::LEN as u64,
+                lamports: Rent::get()?.minimum_balance(Metadata::LEN),
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[signer_seeds])?;
+        } else {
+            check_program_owned(metadata)?;
+        }
+
+        let mut metadata = if is_new {
+            Metadata::init_mut(metadata)?
+        } else {
+            Metadata::load_mut(metadata)?
+        };
+
+        metadata.bump = [self.bump];
+        metadata.name = self.name;
+        metadata.uri = self.uri;
+        metadata.tree_hash = self.tree_hash;
+        metadata.leaf_count = self.leaf_count.into();
+        metadata.depth = [self.depth];
+
+        Ok(())
+    }
+}
+
+/// Creates or rewrites the metadata of a campaign. Only the authority may
+/// call it, and it pays the rent on creation. Unlike the root, metadata can
+/// still be updated after the campaign is closed.
+pub struct SetMetadata<'info> {
+    pub accounts: SetMetadataAccounts<'info>,
+    pub instruction_data: SetMetadataInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for SetMetadata<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = SetMetadataAccounts::try_from(accounts)?;
+        let instruction_data = SetMetadataInstructionData::try_from(data)?;
+
+        Ok(SetMetadata {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> SetMetadata<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &16;
+
+    pub fn process(&mut self) -> ProgramResult {
+        {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+
+            if self.accounts.authority.key().ne(&airdrop_state.authority) {
+                return Err(AirdropProgramError::Unauthorized.into());
+            }
+        }
+
+        self.instruction_data.write(
+            self.accounts.authority,
+            self.accounts.metadata,
+            self.accounts.airdrop_state.key(),
+        )
+    }
+}
@@ -1,15 +1,15 @@
-use core::mem::transmute;
-
 use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
 
 use crate::{
     errors::AirdropProgramError,
     states::AirdropState,
-    utils::{load_acc_mut_unchecked, load_acc_unchecked, DataLen},
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{check_airdrop_state, check_signer, check_system_program, check_vault},
 };
 
 pub struct UpdateMerkleRootAccounts<'info> {
     pub airdrop_state: &'info AccountInfo,
+    pub vault: &'info AccountInfo,
     pub authority: &'info AccountInfo,
 }
 
@@ -17,17 +17,21 @@ impl<'info> TryFrom<&'info [AccountInfo]> for UpdateMerkleRootAccounts<'info> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
-        let [airdrop_state, authority, _] = accounts else {
+        let [airdrop_state, vault, authority, system_program] = accounts else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        // verify airdrop_state
-        if !airdrop_state.is_writable() || airdrop_state.data_is_empty() {
-            return Err(ProgramError::InvalidAccountData);
-        }
+        check_airdrop_state(airdrop_state, true)?;
+        check_vault(vault)?;
+
+        // comparing keys alone would accept an authority that never signed
+        check_signer(authority)?;
+
+        check_system_program(system_program)?;
 
         Ok(UpdateMerkleRootAccounts {
             airdrop_state,
+            vault,
             authority,
         })
     }
@@ -43,6 +47,9 @@ impl DataLen for UpdateMerkleRootInstructionData {
     const LEN: usize = core::mem::size_of::<UpdateMerkleRootInstructionData>();
 }
 
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for UpdateMerkleRootInstructionData {}
+
 impl<'info> TryFrom<&'info [u8]> for UpdateMerkleRootInstructionData {
     type Error = ProgramError;
 
@@ -51,12 +58,7 @@ impl<'info> TryFrom<&'info [u8]> for UpdateMerkleRootInstructionData {
             return Err(ProgramError::InvalidInstructionData);
         }
 
-        Ok(unsafe {
-            transmute(
-                TryInto::<[u8; Self::LEN]>::try_into(data)
-                    .map_err(|_| ProgramError::InvalidInstructionData)?,
-            )
-        })
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
     }
 }
 
@@ -83,14 +85,12 @@ impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for UpdateMerkleRootAir
 
 impl<'info> UpdateMerkleRootAirdrop<'info> {
     pub const DISCRIMINATOR: &'info u8 = &2;
+    /// `sha256("global:update_merkle_root")[..8]`
+    pub const ANCHOR_DISCRIMINATOR: [u8; 8] = [195, 173, 38, 60, 242, 203, 158, 93];
 
     pub fn process(&mut self) -> ProgramResult {
         {
-            let airdrop_state_data = unsafe {
-                load_acc_unchecked::<AirdropState>(
-                    &mut self.accounts.airdrop_state.borrow_data_unchecked(),
-                )?
-            };
+            let airdrop_state_data = AirdropState::load(self.accounts.airdrop_state)?;
 
             if self
                 .accounts
@@ -100,27 +100,31 @@ impl<'info> UpdateMerkleRootAirdrop<'info> {
             {
                 return Err(AirdropProgramError::Unauthorized.into());
             }
+            if airdrop_state_data.is_closed() {
+                return Err(AirdropProgramError::AirdropClosed.into());
+            }
+            airdrop_state_data
+                .validate_vault(self.accounts.vault.key(), self.accounts.airdrop_state.key())?;
+        }
+
+        let additional_amount = self.instruction_data.additional_amount;
+        if additional_amount > 0 {
+            pinocchio_system::instructions::Transfer {
+                from: self.accounts.authority,
+                to: self.accounts.vault,
+                lamports: additional_amount,
+            }
+            .invoke()?;
         }
 
         {
-            let data = unsafe { self.accounts.airdrop_state.borrow_mut_data_unchecked() };
-            let airdrop_state_data = unsafe { load_acc_mut_unchecked::<AirdropState>(data)? };
+            let mut airdrop_state_data = AirdropState::load_mut(self.accounts.airdrop_state)?;
 
             airdrop_state_data.merkle_root = self.instruction_data.new_merkle_root;
-
-            if self.instruction_data.additional_amount > 0 {
-                pinocchio_system::instructions::Transfer {
-                    from: self.accounts.authority,
-                    to: self.accounts.airdrop_state,
-                    lamports: self.instruction_data.additional_amount,
-                }
-                .invoke()?;
-
-                airdrop_state_data.airdrop_amount =
-                    u64::from_le_bytes(airdrop_state_data.airdrop_amount)
-                        .saturating_add(self.instruction_data.additional_amount)
-                        .to_le_bytes();
-            }
+            let airdrop_amount = airdrop_state_data.airdrop_amount.get();
+            airdrop_state_data
+                .airdrop_amount
+                .set(airdrop_amount.saturating_add(additional_amount));
         }
 
         Ok(())
@@ -0,0 +1,292 @@
+use pinocchio::{
+    account_info::AccountInfo, cpi::set_return_data, program_error::ProgramError, ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::{
+        existing_claim_status_error, AirdropState, ClaimStatus, Config, LeafClaimStatus, Redirect,
+    },
+    utils::{
+        proof_from_bytes, verify_merkle_proof, AccountHeader, DataLen, HashFunction, ZeroCopy,
+        MAX_PROOF_LEN,
+    },
+    validation::{check_airdrop_state, check_config},
+};
+
+pub struct VerifyClaimAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    /// The pubkey committed in the leaf, or the new pubkey of a redirect;
+    /// does not need to sign
+    pub recipient: &'info AccountInfo,
+    pub user_claim: &'info AccountInfo,
+    /// Redirect to `recipient`, or the empty redirect PDA of `recipient`
+    pub redirect: &'info AccountInfo,
+    /// Config PDA, possibly not created yet; sets the fee the result reports
+    pub config: &'info AccountInfo,
+    /// Claim authority committed in the leaf, required in
+    /// `MODE_CLAIM_AUTHORITY`; does not need to sign
+    pub claim_authority: Option<&'info AccountInfo>,
+    /// Claimer-keyed status of the recipient, required on campaigns migrated
+    /// to leaf-keyed statuses
+    pub legacy_claim: Option<&'info AccountInfo>,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for VerifyClaimAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, recipient, user_claim, redirect, config, remaining @ ..] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, false)?;
+        check_config(config)?;
+
+        // the claim authority, then the claimer-keyed status, each only when
+        // the campaign needs it
+        let mut remaining = remaining.iter();
+        let (claim_authority, legacy_claim) = {
+            let airdrop_state = AirdropState::load(airdrop_state)?;
+            let mut next_if = |needed: bool| {
+                if needed {
+                    remaining
+                        .next()
+                        .ok_or(ProgramError::NotEnoughAccountKeys)
+                        .map(Some)
+                } else {
+                    Ok(None)
+                }
+            };
+            (
+                next_if(airdrop_state.uses_claim_authority())?,
+                next_if(airdrop_state.is_migrated_to_leaf())?,
+            )
+        };
+        if remaining.next().is_some() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        Ok(VerifyClaimAccounts {
+            airdrop_state,
+            recipient,
+            user_claim,
+            redirect,
+            config,
+            claim_authority,
+            legacy_claim,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct VerifyClaimInstructionData {
+    pub amount: u64,
+    pub leaf_index: u64,
+    /// Bump of `user_claim`, so the lookup needs no PDA search
+    pub bump: u8,
+    pub proof_len: u8,
+}
+
+impl DataLen for VerifyClaimInstructionData {
+    const LEN: usize = core::mem::size_of::<VerifyClaimInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for VerifyClaimInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for VerifyClaimInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let fixed_data = &data[..Self::LEN];
+
+        Self::read_from(fixed_data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Checks a claim without changing any account, for simulation before the
+/// user signs or for other programs through CPI.
+///
+/// The outcome is not an error: the instruction succeeds and sets
+/// `VerifyClaimResult` (status byte, then the amount the recipient would
+/// receive and the protocol fee, both as u64 LE) as return data. Malformed
+/// accounts or data still fail.
+pub struct VerifyClaim<'info> {
+    pub accounts: VerifyClaimAccounts<'info>,
+    pub instruction_data: VerifyClaimInstructionData,
+    pub proof_data: &'info [[u8; 32]],
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for VerifyClaim<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = VerifyClaimAccounts::try_from(accounts)?;
+        let instruction_data = VerifyClaimInstructionData::try_from(data)?;
+
+        // check data length
+        let proof_len = instruction_data.proof_len as usize;
+        if proof_len > MAX_PROOF_LEN {
+            return Err(AirdropProgramError::ProofTooLong.into());
+        }
+        if data.len() != VerifyClaimInstructionData::LEN + proof_len * 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let proof_data = proof_from_bytes(&data[VerifyClaimInstructionData::LEN..])
+            .ok_or(ProgramError::InvalidInstructionData)?;
+
+        Ok(VerifyClaim {
+            accounts,
+            instruction_data,
+            proof_data,
+        })
+    }
+}
+
+/// Return data of `VerifyClaim`
+#[repr(C, packed)]
+pub struct VerifyClaimResult {
+    /// One of the `STATUS_*` constants
+    pub status: u8,
+    /// What the recipient receives when `status` is `STATUS_CLAIMABLE`: the
+    /// leaf amount less a fee withheld from it. 0 otherwise
+    pub claimable: u64,
+    /// Protocol fee the claim pays when `status` is `STATUS_CLAIMABLE`,
+    /// withheld from the amount or paid by the claimer. 0 otherwise
+    pub fee: u64,
+}
+
+impl DataLen for VerifyClaimResult {
+    const LEN: usize = core::mem::size_of::<VerifyClaimResult>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for VerifyClaimResult {}
+
+impl VerifyClaimResult {
+    pub const STATUS_CLAIMABLE: u8 = 0;
+    pub const STATUS_INVALID_PROOF: u8 = 1;
+    pub const STATUS_ALREADY_CLAIMED: u8 = 2;
+    pub const STATUS_REVOKED: u8 = 3;
+    pub const STATUS_CLOSED: u8 = 4;
+    /// The recipient's leaves were reassigned to another key
+    pub const STATUS_REDIRECTED: u8 = 5;
+}
+
+impl<'info> VerifyClaim<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &15;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let amount = self.instruction_data.amount;
+        let leaf_index = self.instruction_data.leaf_index;
+        // a redirected leaf still commits to the old pubkey
+        let leaf_pubkey = Redirect::leaf_pubkey(
+            self.accounts.redirect,
+            self.accounts.airdrop_state.key(),
+            self.accounts.recipient.key(),
+        )?;
+
+        let status = if let Some(recipient) = &leaf_pubkey {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+
+            // the status lookup must point at the right PDA, or a random empty
+            // account would always read as unclaimed
+            if airdrop_state.is_keyed_by_leaf() {
+                LeafClaimStatus::validate_pda(
+                    self.accounts.user_claim.key(),
+                    self.accounts.airdrop_state.key(),
+                    leaf_index,
+                    self.instruction_data.bump,
+                )?;
+            } else {
+                ClaimStatus::validate_pda(
+                    self.accounts.user_claim.key(),
+                    self.accounts.airdrop_state.key(),
+                    recipient,
+                    self.instruction_data.bump,
+                )?;
+            }
+
+            let hash_fn = HashFunction::try_from(airdrop_state.hash_fn[0])?;
+            let leaf = if airdrop_state.uses_claim_authority() {
+                let claim_authority = self
+                    .accounts
+                    .claim_authority
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                hash_fn.create_delegated_airdrop_leaf(recipient, claim_authority.key(), amount, 0)
+            } else {
+                hash_fn.create_airdrop_leaf(recipient, amount, 0)
+            };
+            let prior_claim = match self.accounts.legacy_claim {
+                Some(legacy_claim) => ClaimStatus::prior_claim(
+                    legacy_claim,
+                    &[
+                        ClaimStatus::SEED,
+                        self.accounts.airdrop_state.key().as_ref(),
+                        recipient.as_ref(),
+                    ],
+                )?,
+                None => None,
+            };
+            let is_valid = leaf.is_some_and(|leaf| {
+                verify_merkle_proof(
+                    hash_fn,
+                    &leaf,
+                    self.proof_data,
+                    leaf_index,
+                    &airdrop_state.merkle_root,
+                )
+            });
+
+            if airdrop_state.is_closed() {
+                VerifyClaimResult::STATUS_CLOSED
+            } else if !is_valid {
+                VerifyClaimResult::STATUS_INVALID_PROOF
+            } else if let Some(error) = prior_claim.or_else(|| {
+                (!self.accounts.user_claim.data_is_empty())
+                    .then(|| existing_claim_status_error(self.accounts.user_claim))
+            }) {
+                if error == AirdropProgramError::LeafRevoked {
+                    VerifyClaimResult::STATUS_REVOKED
+                } else {
+                    VerifyClaimResult::STATUS_ALREADY_CLAIMED
+                }
+            } else {
+                VerifyClaimResult::STATUS_CLAIMABLE
+            }
+        } else {
+            VerifyClaimResult::STATUS_REDIRECTED
+        };
+
+        let (claimable, fee) = if status != VerifyClaimResult::STATUS_CLAIMABLE {
+            (0, 0)
+        } else if self.accounts.config.data_is_empty() {
+            (amount, 0)
+        } else {
+            let config = Config::load(self.accounts.config)?;
+            let fee = config.fee_for(amount);
+            if config.is_paid_by_claimer() {
+                (amount, fee)
+            } else {
+                (amount - fee, fee)
+            }
+        };
+
+        let mut result = [0u8; VerifyClaimResult::LEN];
+        result[0] = status;
+        result[1..9].copy_from_slice(&claimable.to_le_bytes());
+        result[9..].copy_from_slice(&fee.to_le_bytes());
+        set_return_data(&result);
+
+        Ok(())
+    }
+}
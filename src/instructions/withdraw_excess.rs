@@ -0,0 +1,124 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::AirdropState,
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{check_airdrop_state, check_signer, check_vault, check_writable},
+};
+
+pub struct WithdrawExcessAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub vault: &'info AccountInfo,
+    pub authority: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for WithdrawExcessAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, vault, authority] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, false)?;
+        check_vault(vault)?;
+
+        check_signer(authority)?;
+        check_writable(authority)?;
+
+        Ok(WithdrawExcessAccounts {
+            airdrop_state,
+            vault,
+            authority,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct WithdrawExcessInstructionData {
+    pub amount: u64,
+}
+
+impl DataLen for WithdrawExcessInstructionData {
+    const LEN: usize = core::mem::size_of::<WithdrawExcessInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for WithdrawExcessInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for WithdrawExcessInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Self::read_from(data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Sends `amount` from the vault to the authority. Only the balance above the
+/// vault's rent and the outstanding `airdrop_amount - amount_claimed` can be
+/// withdrawn, so every unclaimed leaf stays covered.
+pub struct WithdrawExcess<'info> {
+    pub accounts: WithdrawExcessAccounts<'info>,
+    pub instruction_data: WithdrawExcessInstructionData,
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for WithdrawExcess<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = WithdrawExcessAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawExcessInstructionData::try_from(data)?;
+
+        Ok(WithdrawExcess {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'info> WithdrawExcess<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &14;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let outstanding = {
+            let airdrop_state = AirdropState::load(self.accounts.airdrop_state)?;
+
+            if self.accounts.authority.key().ne(&airdrop_state.authority) {
+                return Err(AirdropProgramError::Unauthorized.into());
+            }
+            airdrop_state
+                .validate_vault(self.accounts.vault.key(), self.accounts.airdrop_state.key())?;
+            airdrop_state
+                .outstanding()
+                .ok_or(ProgramError::InvalidAccountData)?
+        };
+
+        let reserved = Rent::get()?
+            .minimum_balance(0)
+            .checked_add(outstanding)
+            .ok_or(AirdropProgramError::ArithmeticOverflow)?;
+        let excess = self.accounts.vault.lamports().saturating_sub(reserved);
+
+        let amount = self.instruction_data.amount;
+        if amount == 0 || amount > excess {
+            return Err(AirdropProgramError::InsufficientFunds.into());
+        }
+
+        *self.accounts.vault.try_borrow_mut_lamports()? -= amount;
+        *self.accounts.authority.try_borrow_mut_lamports()? += amount;
+
+        Ok(())
+    }
+}
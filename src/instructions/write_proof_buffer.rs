@@ -0,0 +1,175 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::ProofBuffer,
+    utils::{AccountHeader, DataLen, ZeroCopy, MAX_PROOF_LEN},
+    validation::{
+        check_airdrop_state, check_program_owned, check_signer, check_system_program,
+        check_writable,
+    },
+};
+
+pub struct WriteProofBufferAccounts<'info> {
+    pub airdrop_state: &'info AccountInfo,
+    pub claimer: &'info AccountInfo,
+    pub proof_buffer: &'info AccountInfo,
+}
+
+impl<'info> TryFrom<&'info [AccountInfo]> for WriteProofBufferAccounts<'info> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+        let [airdrop_state, claimer, proof_buffer, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_airdrop_state(airdrop_state, false)?;
+
+        check_signer(claimer)?;
+        check_writable(claimer)?;
+
+        check_writable(proof_buffer)?;
+
+        check_system_program(system_program)?;
+
+        Ok(WriteProofBufferAccounts {
+            airdrop_state,
+            claimer,
+            proof_buffer,
+        })
+    }
+}
+
+#[repr(C, packed)]
+pub struct WriteProofBufferInstructionData {
+    pub bump: u8,
+    /// Total number of proof elements the buffer holds
+    pub proof_len: u8,
+    /// Index of the first element written by this chunk
+    pub offset: u8,
+}
+
+impl DataLen for WriteProofBufferInstructionData {
+    const LEN: usize = core::mem::size_of::<WriteProofBufferInstructionData>();
+}
+
+// SAFETY: packed, and every field is an integer or a byte array
+unsafe impl ZeroCopy for WriteProofBufferInstructionData {}
+
+impl<'info> TryFrom<&'info [u8]> for WriteProofBufferInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'info [u8]) -> Result<Self, Self::Error> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let fixed_data = &data[..Self::LEN];
+
+        Self::read_from(fixed_data).ok_or(ProgramError::InvalidInstructionData)
+    }
+}
+
+/// Stages a chunk of a merkle proof into the claimer's proof buffer, creating
+/// the buffer on first use. `ClaimAirdrop` consumes and closes it; a buffer
+/// no claim consumes is closed with `CloseProofBuffer`.
+pub struct WriteProofBuffer<'info> {
+    pub accounts: WriteProofBufferAccounts<'info>,
+    pub instruction_data: WriteProofBufferInstructionData,
+    pub chunk: &'info [u8],
+}
+
+impl<'info> TryFrom<(&'info [u8], &'info [AccountInfo])> for WriteProofBuffer<'info> {
+    type Error = ProgramError;
+
+    fn try_from(
+        (data, accounts): (&'info [u8], &'info [AccountInfo]),
+    ) -> Result<Self, Self::Error> {
+        let accounts = WriteProofBufferAccounts::try_from(accounts)?;
+        let instruction_data = WriteProofBufferInstructionData::try_from(data)?;
+
+        if instruction_data.proof_len as usize > MAX_PROOF_LEN {
+            return Err(AirdropProgramError::ProofTooLong.into());
+        }
+
+        let chunk = &data[WriteProofBufferInstructionData::LEN..];
+        if chunk.len() % 32 != 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let end = instruction_data.offset as usize + chunk.len() / 32;
+        if end > instruction_data.proof_len as usize {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(WriteProofBuffer {
+            accounts,
+            instruction_data,
+            chunk,
+        })
+    }
+}
+
+impl<'info> WriteProofBuffer<'info> {
+    pub const DISCRIMINATOR: &'info u8 = &6;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let bump = self.instruction_data.bump;
+        let proof_len = self.instruction_data.proof_len;
+
+        ProofBuffer::validate_pda(
+            self.accounts.proof_buffer.key(),
+            self.accounts.airdrop_state.key(),
+            self.accounts.claimer.key(),
+            bump,
+        )?;
+
+        if self.accounts.proof_buffer.data_is_empty() {
+            // create the buffer on the first chunk
+            let bump_binding = [bump];
+            let seed = [
+                Seed::from(ProofBuffer::SEED),
+                Seed::from(self.accounts.airdrop_state.key().as_ref()),
+                Seed::from(self.accounts.claimer.key().as_ref()),
+                Seed::from(&bump_binding),
+            ];
+            let signer_seeds = Signer::from(&seed);
+            let space = ProofBuffer::space(proof_len);
+
+            pinocchio_system::instructions::CreateAccount {
+                from: self.accounts.claimer,
+                to: self.accounts.proof_buffer,
+                space: space as u64,
+                lamports: Rent::get()?.minimum_balance(space),
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[signer_seeds])?;
+
+            let mut data = self.accounts.proof_buffer.try_borrow_mut_data()?;
+            let header = ProofBuffer::init_bytes(&mut data[..ProofBuffer::LEN])?;
+            header.bump = [bump];
+            header.proof_len = [proof_len];
+        } else {
+            check_program_owned(self.accounts.proof_buffer)?;
+        }
+
+        let mut data = self.accounts.proof_buffer.try_borrow_mut_data()?;
+        {
+            let (header, _) = ProofBuffer::unpack(&data)?;
+            if header.proof_len[0] != proof_len {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+        }
+
+        let start = ProofBuffer::LEN + self.instruction_data.offset as usize * 32;
+        data[start..start + self.chunk.len()].copy_from_slice(self.chunk);
+
+        Ok(())
+    }
+}
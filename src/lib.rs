@@ -1,16 +1,18 @@
 #![no_std]
 #![allow(unexpected_cfgs)]
-use pinocchio::{no_allocator, program_entrypoint};
+pub mod cpi;
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod processor;
 pub mod states;
 pub mod utils;
-
-use processor::process_instruction;
+pub mod validation;
 
 pinocchio_pubkey::declare_id!("FoNu94ZtecyvwuJ1BvKXkmpbGFaiZ5TCcpe9yXdcQbr2");
 
-program_entrypoint!(process_instruction);
-no_allocator!();
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::program_entrypoint!(processor::process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::no_allocator!();
 // nostd_panic_handler!();
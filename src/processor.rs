@@ -2,13 +2,57 @@ use pinocchio::{
     account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
 };
 
-use crate::instructions::{ClaimAirdrop, InitializeAirdrop, UpdateMerkleRootAirdrop};
+use crate::instructions::{
+    ClaimAirdrop, ClaimBatch, CloseAirdrop, CloseClaimStatus, CloseProofBuffer, Deposit,
+    InitializeAirdrop, MigrateClaimKey, MigrateState, ReassignLeaf, RevokeLeaf, SetConfig,
+    SetCpiPolicy, SetMetadata, UpdateMerkleRootAirdrop, VerifyClaim, WithdrawExcess,
+    WriteProofBuffer,
+};
+
+#[cfg(feature = "logging")]
+use crate::errors::AirdropProgramError;
+#[cfg(feature = "logging")]
+use pinocchio::program_error::ToStr;
+
+#[cfg(feature = "evm")]
+use crate::instructions::ClaimEvm;
+#[cfg(feature = "zk")]
+use crate::instructions::{ClaimZk, InitializeZkConfig};
 
 pub fn process_instruction(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
+    let result = dispatch(accounts, instruction_data);
+
+    // only the error the instruction fails with is logged; errors the
+    // instructions create and discard along the way stay silent
+    #[cfg(feature = "logging")]
+    if let Err(error) = &result {
+        pinocchio_log::logger::log_message(error.to_str::<AirdropProgramError>().as_bytes());
+    }
+
+    result
+}
+
+fn dispatch(accounts: &[AccountInfo], instruction_data: &[u8]) -> ProgramResult {
+    // none of the Anchor discriminators starts with a one-byte discriminator
+    // in use, so both forms can be told apart by the first byte
+    #[cfg(feature = "anchor-discriminators")]
+    match instruction_data.split_first_chunk::<8>() {
+        Some((&InitializeAirdrop::ANCHOR_DISCRIMINATOR, data)) => {
+            return InitializeAirdrop::try_from((data, accounts))?.process()
+        }
+        Some((&ClaimAirdrop::ANCHOR_DISCRIMINATOR, data)) => {
+            return ClaimAirdrop::try_from((data, accounts))?.process()
+        }
+        Some((&UpdateMerkleRootAirdrop::ANCHOR_DISCRIMINATOR, data)) => {
+            return UpdateMerkleRootAirdrop::try_from((data, accounts))?.process()
+        }
+        _ => {}
+    }
+
     match instruction_data.split_first() {
         Some((InitializeAirdrop::DISCRIMINATOR, data)) => {
             InitializeAirdrop::try_from((data, accounts))?.process()
@@ -19,6 +63,55 @@ pub fn process_instruction(
         Some((UpdateMerkleRootAirdrop::DISCRIMINATOR, data)) => {
             UpdateMerkleRootAirdrop::try_from((data, accounts))?.process()
         }
+        Some((CloseAirdrop::DISCRIMINATOR, data)) => {
+            CloseAirdrop::try_from((data, accounts))?.process()
+        }
+        Some((CloseClaimStatus::DISCRIMINATOR, data)) => {
+            CloseClaimStatus::try_from((data, accounts))?.process()
+        }
+        Some((ClaimBatch::DISCRIMINATOR, data)) => {
+            ClaimBatch::try_from((data, accounts))?.process()
+        }
+        Some((WriteProofBuffer::DISCRIMINATOR, data)) => {
+            WriteProofBuffer::try_from((data, accounts))?.process()
+        }
+        Some((MigrateClaimKey::DISCRIMINATOR, data)) => {
+            MigrateClaimKey::try_from((data, accounts))?.process()
+        }
+        Some((RevokeLeaf::DISCRIMINATOR, data)) => {
+            RevokeLeaf::try_from((data, accounts))?.process()
+        }
+        Some((ReassignLeaf::DISCRIMINATOR, data)) => {
+            ReassignLeaf::try_from((data, accounts))?.process()
+        }
+        Some((Deposit::DISCRIMINATOR, data)) => Deposit::try_from((data, accounts))?.process(),
+        Some((WithdrawExcess::DISCRIMINATOR, data)) => {
+            WithdrawExcess::try_from((data, accounts))?.process()
+        }
+        Some((VerifyClaim::DISCRIMINATOR, data)) => {
+            VerifyClaim::try_from((data, accounts))?.process()
+        }
+        Some((SetMetadata::DISCRIMINATOR, data)) => {
+            SetMetadata::try_from((data, accounts))?.process()
+        }
+        Some((MigrateState::DISCRIMINATOR, data)) => {
+            MigrateState::try_from((data, accounts))?.process()
+        }
+        Some((SetCpiPolicy::DISCRIMINATOR, data)) => {
+            SetCpiPolicy::try_from((data, accounts))?.process()
+        }
+        Some((SetConfig::DISCRIMINATOR, data)) => SetConfig::try_from((data, accounts))?.process(),
+        Some((CloseProofBuffer::DISCRIMINATOR, data)) => {
+            CloseProofBuffer::try_from((data, accounts))?.process()
+        }
+        #[cfg(feature = "evm")]
+        Some((ClaimEvm::DISCRIMINATOR, data)) => ClaimEvm::try_from((data, accounts))?.process(),
+        #[cfg(feature = "zk")]
+        Some((InitializeZkConfig::DISCRIMINATOR, data)) => {
+            InitializeZkConfig::try_from((data, accounts))?.process()
+        }
+        #[cfg(feature = "zk")]
+        Some((ClaimZk::DISCRIMINATOR, data)) => ClaimZk::try_from((data, accounts))?.process(),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
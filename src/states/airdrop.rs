@@ -1,37 +1,217 @@
-use pinocchio::{
-    program_error::ProgramError,
-    pubkey::{self, Pubkey},
-};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
 
-use crate::utils::DataLen;
+use crate::{
+    errors::AirdropProgramError,
+    states::Vault,
+    utils::{AccountHeader, DataLen, PodU64, ZeroCopy, ACCOUNT_HEADER_LEN},
+    validation::check_canonical_pda,
+};
 
 #[repr(C)]
 pub struct AirdropState {
+    /// `AccountHeader::DISCRIMINATOR` of this type
+    pub discriminator: [u8; 8],
+    /// Layout version, `AccountHeader::VERSION` for accounts this program writes
+    pub version: [u8; 1],
     /// The Merkle root of the airdrop (32 bytes)
     pub merkle_root: [u8; 32],
     /// The authority allowed to update the merkle root
     pub authority: Pubkey,
     /// Total SOL allocated for this airdrop (in lamports)
-    pub airdrop_amount: [u8; 8],
+    pub airdrop_amount: PodU64,
     /// Total SOL claimed so far (in lamports)
-    pub amount_claimed: [u8; 8],
+    pub amount_claimed: PodU64,
     /// Bump seed for the PDA
     pub bump: [u8; 1],
+    /// Set once the authority closes the campaign; no further claims are accepted
+    pub closed: [u8; 1],
+    /// Who may execute claims on behalf of recipients (`MODE_OPERATOR` only)
+    pub operator: Pubkey,
+    /// One of `MODE_SELF_CLAIM`, `MODE_OPERATOR`, `MODE_PERMISSIONLESS` or
+    /// `MODE_CLAIM_AUTHORITY`
+    pub distribution_mode: [u8; 1],
+    /// `HashFunction` used for the leaves and nodes of the tree
+    pub hash_fn: [u8; 1],
+    /// `CLAIM_KEY_CLAIMER`, `CLAIM_KEY_LEAF_INDEX` or `CLAIM_KEY_MIGRATED`:
+    /// what claim statuses are keyed by
+    pub claim_key: [u8; 1],
+    /// Bump seed of the `Vault` PDA holding the funds
+    pub vault_bump: [u8; 1],
+    /// `CPI_ALLOW_ALL`, `CPI_DENY` or `CPI_ALLOWLIST`: who may claim through CPI
+    pub cpi_policy: [u8; 1],
+    /// Number of programs in `cpi_callers` (`CPI_ALLOWLIST` only)
+    pub cpi_caller_count: [u8; 1],
+    /// Programs that may claim through CPI (`CPI_ALLOWLIST` only)
+    pub cpi_callers: [Pubkey; AirdropState::MAX_CPI_CALLERS],
 }
 
 impl DataLen for AirdropState {
     const LEN: usize = core::mem::size_of::<AirdropState>();
 }
 
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for AirdropState {}
+
+impl AccountHeader for AirdropState {
+    const DISCRIMINATOR: [u8; 8] = [1, 49, 110, 205, 185, 136, 198, 165];
+    const VERSION: u8 = 2;
+}
+
+/// Headerless layout of the first deployment, read as version 0 by
+/// `MigrateState`. The state account held the funds itself, and
+/// `ClaimAirdrop` wrote `amount_claimed` big-endian.
+#[repr(C)]
+pub struct AirdropStateV0 {
+    pub merkle_root: [u8; 32],
+    pub authority: Pubkey,
+    pub airdrop_amount: [u8; 8],
+    pub amount_claimed: [u8; 8],
+    pub bump: [u8; 1],
+}
+
+impl DataLen for AirdropStateV0 {
+    const LEN: usize = core::mem::size_of::<AirdropStateV0>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for AirdropStateV0 {}
+
+/// Layout of version 1, before the CPI policy was added. `MigrateState`
+/// extends it in place.
+#[repr(C)]
+pub struct AirdropStateV1 {
+    pub discriminator: [u8; 8],
+    pub version: [u8; 1],
+    pub merkle_root: [u8; 32],
+    pub authority: Pubkey,
+    pub airdrop_amount: PodU64,
+    pub amount_claimed: PodU64,
+    pub bump: [u8; 1],
+    pub closed: [u8; 1],
+    pub operator: Pubkey,
+    pub distribution_mode: [u8; 1],
+    pub hash_fn: [u8; 1],
+    pub claim_key: [u8; 1],
+    pub vault_bump: [u8; 1],
+}
+
+impl DataLen for AirdropStateV1 {
+    const LEN: usize = core::mem::size_of::<AirdropStateV1>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for AirdropStateV1 {}
+
 impl AirdropState {
     pub const SEED: &'static [u8] = b"merkle_tree";
 
+    /// Recipients sign their own claims
+    pub const MODE_SELF_CLAIM: u8 = 0;
+    /// The operator may also push claims to recipients
+    pub const MODE_OPERATOR: u8 = 1;
+    /// Anyone may push claims to recipients
+    pub const MODE_PERMISSIONLESS: u8 = 2;
+    /// Leaves commit to a beneficiary and a claim authority; the claim
+    /// authority signs, through CPI for a PDA, and the beneficiary is paid
+    pub const MODE_CLAIM_AUTHORITY: u8 = 3;
+
+    /// One claim per wallet: statuses derive from `(airdrop, claimer)`
+    pub const CLAIM_KEY_CLAIMER: u8 = 0;
+    /// One claim per leaf: statuses derive from `(airdrop, leaf_index)`
+    pub const CLAIM_KEY_LEAF_INDEX: u8 = 1;
+    /// Keyed by leaf after `MigrateClaimKey`: claims also require the
+    /// claimer-keyed status of the leaf's pubkey to be empty, as the claims
+    /// made before the switch are recorded there
+    pub const CLAIM_KEY_MIGRATED: u8 = 2;
+
+    /// Claims may come from any program
+    pub const CPI_ALLOW_ALL: u8 = 0;
+    /// Claims must be top-level instructions
+    pub const CPI_DENY: u8 = 1;
+    /// Claims must be top-level or come from a program in `cpi_callers`
+    pub const CPI_ALLOWLIST: u8 = 2;
+    pub const MAX_CPI_CALLERS: usize = 4;
+
+    /// Check that `target` is the campaign PDA at its canonical bump
     pub fn validate_pda(target: &Pubkey, bump: u8) -> Result<(), ProgramError> {
-        let seed_with_bump = &[Self::SEED, &[bump]];
-        let expected = pubkey::create_program_address(seed_with_bump, &crate::ID)?;
-        if expected != *target {
-            return Err(ProgramError::InvalidAccountData);
+        check_canonical_pda(target, &[Self::SEED], bump)
+    }
+
+    /// Check that `vault` is the vault of the campaign at `airdrop`
+    pub fn validate_vault(&self, vault: &Pubkey, airdrop: &Pubkey) -> Result<(), ProgramError> {
+        Vault::validate_pda(vault, airdrop, self.vault_bump[0])
+    }
+
+    /// Layout version of the campaign account holding `bytes`: the header
+    /// version, or 0 for a headerless `AirdropStateV0`.
+    pub fn layout_version(bytes: &[u8]) -> Result<u8, ProgramError> {
+        if bytes.len() >= ACCOUNT_HEADER_LEN && bytes[..8] == Self::DISCRIMINATOR {
+            Ok(bytes[8])
+        } else if bytes.len() == AirdropStateV0::LEN {
+            Ok(0)
+        } else {
+            Err(AirdropProgramError::InvalidAccountDiscriminator.into())
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed[0] != 0
+    }
+
+    /// Lamports still owed to recipients: nothing once the campaign is
+    /// closed. `None` if the counters are inconsistent.
+    pub fn outstanding(&self) -> Option<u64> {
+        if self.is_closed() {
+            return Some(0);
+        }
+        self.airdrop_amount
+            .get()
+            .checked_sub(self.amount_claimed.get())
+    }
+
+    pub fn is_keyed_by_leaf(&self) -> bool {
+        self.claim_key[0] != Self::CLAIM_KEY_CLAIMER
+    }
+
+    /// Whether claims must also check the claimer-keyed status of the leaf
+    pub fn is_migrated_to_leaf(&self) -> bool {
+        self.claim_key[0] == Self::CLAIM_KEY_MIGRATED
+    }
+
+    /// Whether leaves commit to a claim authority besides the beneficiary
+    pub fn uses_claim_authority(&self) -> bool {
+        self.distribution_mode[0] == Self::MODE_CLAIM_AUTHORITY
+    }
+
+    /// Whether `signer` may execute the claim of `recipient`'s leaf. Funds
+    /// always go to the recipient committed in the leaf.
+    pub fn can_claim_for(&self, signer: &Pubkey, recipient: &Pubkey) -> bool {
+        if signer == recipient {
+            return true;
+        }
+        match self.distribution_mode[0] {
+            Self::MODE_OPERATOR => *signer == self.operator,
+            Self::MODE_PERMISSIONLESS => true,
+            // the leaf commits to the signer, so the proof decides
+            Self::MODE_CLAIM_AUTHORITY => true,
+            _ => false,
+        }
+    }
+
+    /// Whether the top-level instruction of a claim may run `program`. A
+    /// claim sent directly to this program is always allowed.
+    pub fn allows_caller(&self, program: &Pubkey) -> bool {
+        if *program == crate::ID {
+            return true;
+        }
+        match self.cpi_policy[0] {
+            Self::CPI_ALLOW_ALL => true,
+            Self::CPI_ALLOWLIST => self
+                .cpi_callers
+                .iter()
+                .take(self.cpi_caller_count[0] as usize)
+                .any(|caller| caller == program),
+            _ => false,
         }
-        Ok(())
     }
 }
@@ -1,32 +1,270 @@
 use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
     program_error::ProgramError,
     pubkey::{self, Pubkey},
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
 };
 
-use crate::utils::DataLen;
+use crate::{
+    errors::AirdropProgramError,
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::check_canonical_pda,
+};
 
+/// Error for a claim status that already exists: the leaf was either claimed
+/// or revoked.
+pub fn existing_claim_status_error(user_claim: &AccountInfo) -> AirdropProgramError {
+    let revoked = user_claim.is_owned_by(&crate::ID)
+        && user_claim.try_borrow_data().is_ok_and(|data| {
+            ClaimStatus::from_bytes(&data)
+                .map(ClaimStatus::is_revoked)
+                .or_else(|_| LeafClaimStatus::from_bytes(&data).map(LeafClaimStatus::is_revoked))
+                .unwrap_or(false)
+        });
+    if revoked {
+        AirdropProgramError::LeafRevoked
+    } else {
+        AirdropProgramError::AccountAlreadyClaimed
+    }
+}
+
+/// Claim status keyed by the claimer's pubkey or EVM address. Records who
+/// paid the rent, as an operator or a relayer may have submitted the claim.
 #[repr(C)]
 pub struct ClaimStatus {
+    /// `AccountHeader::DISCRIMINATOR` of this type
+    pub discriminator: [u8; 8],
+    /// Layout version, `AccountHeader::VERSION` for accounts this program writes
+    pub version: [u8; 1],
     pub bump: [u8; 1],
+    /// Set when the authority revoked the leaf instead of it being claimed
+    pub revoked: [u8; 1],
+    pub payer: Pubkey,
 }
 impl DataLen for ClaimStatus {
     const LEN: usize = core::mem::size_of::<ClaimStatus>();
 }
 
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for ClaimStatus {}
+
+impl AccountHeader for ClaimStatus {
+    const DISCRIMINATOR: [u8; 8] = [22, 183, 249, 157, 247, 95, 150, 96];
+    const VERSION: u8 = 1;
+}
+
 impl ClaimStatus {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked[0] != 0
+    }
+
     pub const SEED: &'static [u8] = b"claim";
+    pub const EVM_SEED: &'static [u8] = b"evm_claim";
+
+    /// Check that `target` is the status of `claimer` at its canonical bump,
+    /// so each claimer has exactly one status address.
+    pub fn validate_pda(
+        target: &Pubkey,
+        airdrop: &Pubkey,
+        claimer: &Pubkey,
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        check_canonical_pda(
+            target,
+            &[Self::SEED, airdrop.as_ref(), claimer.as_ref()],
+            bump,
+        )
+    }
+
+    /// What was recorded at the claimer-keyed status `account`, which must be
+    /// the canonical PDA of `seeds`: `None` while it is empty, otherwise the
+    /// error a claim of it fails with. Campaigns migrated to leaf-keyed
+    /// statuses check it for every claim, so a leaf claimed before the switch
+    /// cannot be claimed again. The PDA search costs compute, but only those
+    /// campaigns pay it.
+    pub fn prior_claim(
+        account: &AccountInfo,
+        seeds: &[&[u8]],
+    ) -> Result<Option<AirdropProgramError>, ProgramError> {
+        let (expected, _) = pubkey::find_program_address(seeds, &crate::ID);
+        if expected != *account.key() {
+            return Err(AirdropProgramError::InvalidPda.into());
+        }
+        if account.data_is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(existing_claim_status_error(account)))
+    }
+
+    /// Claim status of a leaf committed to an EVM address, at its canonical
+    /// bump
+    pub fn validate_evm_pda(
+        target: &Pubkey,
+        airdrop: &Pubkey,
+        address: &[u8; 20],
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        check_canonical_pda(
+            target,
+            &[Self::EVM_SEED, airdrop.as_ref(), address.as_ref()],
+            bump,
+        )
+    }
+
+    /// Create the status at `target`, the PDA of `seeds` (`SEED` or
+    /// `EVM_SEED`, the airdrop, then the claimer or the EVM address) at its
+    /// canonical `bump`, failing if the leaf was claimed or revoked.
+    pub fn init(
+        payer: &AccountInfo,
+        target: &AccountInfo,
+        seeds: [&[u8]; 3],
+        bump: u8,
+    ) -> ProgramResult {
+        check_canonical_pda(target.key(), &seeds, bump)?;
+
+        let bump_binding = [bump];
+        let seeds_with_bump = [seeds[0], seeds[1], seeds[2], &bump_binding];
+        let seed = seeds_with_bump.map(Seed::from);
+        let signer_seeds = Signer::from(&seed);
+
+        pinocchio_system::instructions::CreateAccount {
+            from: payer,
+            to: target,
+            space: Self::LEN as u64,
+            lamports: Rent::get()?.minimum_balance(Self::LEN),
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[signer_seeds])?;
+
+        let mut status = ClaimStatus::init_mut(target)?;
+        status.bump = [bump];
+        status.payer = *payer.key();
+        Ok(())
+    }
+}
 
+/// Headerless layout of the first deployment: only the bump. Such statuses
+/// are never rewritten, but `CloseClaimStatus` still accepts them so their
+/// rent can be reclaimed.
+#[repr(C)]
+pub struct ClaimStatusV0 {
+    pub bump: [u8; 1],
+}
+
+impl DataLen for ClaimStatusV0 {
+    const LEN: usize = core::mem::size_of::<ClaimStatusV0>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for ClaimStatusV0 {}
+
+impl ClaimStatusV0 {
+    /// The first deployment took any bump the claimer passed, so its statuses
+    /// are checked at their stored bump rather than the canonical one.
     pub fn validate_pda(
         target: &Pubkey,
         airdrop: &Pubkey,
         claimer: &Pubkey,
         bump: u8,
     ) -> Result<(), ProgramError> {
-        let seed_with_bump = &[Self::SEED, airdrop.as_ref(), claimer.as_ref(), &[bump]];
+        let seed_with_bump = &[
+            ClaimStatus::SEED,
+            airdrop.as_ref(),
+            claimer.as_ref(),
+            &[bump],
+        ];
         let expected = pubkey::create_program_address(seed_with_bump, &crate::ID)?;
         if expected != *target {
-            return Err(ProgramError::InvalidAccountData);
+            return Err(AirdropProgramError::InvalidPda.into());
+        }
+        Ok(())
+    }
+}
+
+/// Claim status of a campaign keyed by leaf index, so a wallet holding several
+/// leaves can claim each of them once. Records who paid the rent, as the leaf
+/// index alone does not say who may reclaim it.
+#[repr(C)]
+pub struct LeafClaimStatus {
+    /// `AccountHeader::DISCRIMINATOR` of this type
+    pub discriminator: [u8; 8],
+    /// Layout version, `AccountHeader::VERSION` for accounts this program writes
+    pub version: [u8; 1],
+    pub bump: [u8; 1],
+    /// Set when the authority revoked the leaf instead of it being claimed
+    pub revoked: [u8; 1],
+    pub payer: Pubkey,
+}
+
+impl DataLen for LeafClaimStatus {
+    const LEN: usize = core::mem::size_of::<LeafClaimStatus>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for LeafClaimStatus {}
+
+impl AccountHeader for LeafClaimStatus {
+    const DISCRIMINATOR: [u8; 8] = [218, 209, 134, 186, 137, 73, 125, 156];
+    const VERSION: u8 = 1;
+}
+
+impl LeafClaimStatus {
+    pub fn is_revoked(&self) -> bool {
+        self.revoked[0] != 0
+    }
+
+    pub const SEED: &'static [u8] = b"leaf_claim";
+
+    /// Check that `target` is the status of `leaf_index` at its canonical
+    /// bump, so each leaf has exactly one status address.
+    pub fn validate_pda(
+        target: &Pubkey,
+        airdrop: &Pubkey,
+        leaf_index: u64,
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        let leaf_index = leaf_index.to_le_bytes();
+        check_canonical_pda(
+            target,
+            &[Self::SEED, airdrop.as_ref(), leaf_index.as_ref()],
+            bump,
+        )
+    }
+
+    /// Create the status of `leaf_index`, failing if the leaf was claimed.
+    pub fn init(
+        payer: &AccountInfo,
+        target: &AccountInfo,
+        airdrop: &Pubkey,
+        leaf_index: u64,
+        bump: u8,
+    ) -> ProgramResult {
+        Self::validate_pda(target.key(), airdrop, leaf_index, bump)?;
+
+        let leaf_index_binding = leaf_index.to_le_bytes();
+        let bump_binding = [bump];
+        let seed = [
+            Seed::from(Self::SEED),
+            Seed::from(airdrop.as_ref()),
+            Seed::from(&leaf_index_binding),
+            Seed::from(&bump_binding),
+        ];
+        let signer_seeds = Signer::from(&seed);
+
+        pinocchio_system::instructions::CreateAccount {
+            from: payer,
+            to: target,
+            space: Self::LEN as u64,
+            lamports: Rent::get()?.minimum_balance(Self::LEN),
+            owner: &crate::ID,
         }
+        .invoke_signed(&[signer_seeds])?;
+
+        let mut status = LeafClaimStatus::init_mut(target)?;
+        status.bump = [bump];
+        status.payer = *payer.key();
         Ok(())
     }
 }
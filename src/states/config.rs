@@ -0,0 +1,173 @@
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+use crate::{
+    states::Vault,
+    utils::{AccountHeader, DataLen, PodU64, ZeroCopy},
+    validation::{check_config, check_writable},
+};
+
+/// Program-wide settings of the protocol fee, at a single PDA (`b"config"`).
+/// Until it is created no fee is charged. Once it is, every claim pays `fee`
+/// to `treasury`.
+#[repr(C)]
+pub struct Config {
+    /// `AccountHeader::DISCRIMINATOR` of this type
+    pub discriminator: [u8; 8],
+    /// Layout version, `AccountHeader::VERSION` for accounts this program writes
+    pub version: [u8; 1],
+    /// May change the config; the program's upgrade authority creates it
+    pub admin: Pubkey,
+    /// Receives the protocol fees
+    pub treasury: Pubkey,
+    /// `FEE_NONE`, `FEE_BPS` or `FEE_FLAT`
+    pub fee_mode: [u8; 1],
+    /// Basis points of the claimed amount (`FEE_BPS`) or lamports per claim
+    /// (`FEE_FLAT`)
+    pub fee: PodU64,
+    /// `FEE_FROM_AMOUNT` or `FEE_FROM_CLAIMER`: who pays the fee
+    pub fee_source: [u8; 1],
+}
+
+impl DataLen for Config {
+    const LEN: usize = core::mem::size_of::<Config>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for Config {}
+
+impl AccountHeader for Config {
+    const DISCRIMINATOR: [u8; 8] = [155, 12, 170, 224, 30, 250, 204, 130];
+    const VERSION: u8 = 1;
+}
+
+impl Config {
+    pub const SEED: &'static [u8] = b"config";
+    /// Canonical bump of the config PDA. The address is fixed at compile time
+    /// so claims can check it without a syscall; a test checks both against
+    /// `find_program_address`.
+    pub const BUMP: u8 = 255;
+    pub const ADDRESS: Pubkey =
+        pinocchio_pubkey::derive_address_const(&[Self::SEED], Some(Self::BUMP), &crate::ID);
+
+    /// No fee is charged
+    pub const FEE_NONE: u8 = 0;
+    /// `fee` basis points of every claimed amount
+    pub const FEE_BPS: u8 = 1;
+    /// `fee` lamports per claimed leaf
+    pub const FEE_FLAT: u8 = 2;
+    pub const MAX_FEE_BPS: u64 = 10_000;
+
+    /// The fee is withheld from the claimed amount
+    pub const FEE_FROM_AMOUNT: u8 = 0;
+    /// The claimer pays the fee on top; the recipient gets the full amount
+    pub const FEE_FROM_CLAIMER: u8 = 1;
+
+    /// Fee owed on a claim of `amount`. A fee withheld from the amount never
+    /// exceeds it.
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        let fee = match self.fee_mode[0] {
+            Self::FEE_BPS => {
+                (amount as u128 * self.fee.get() as u128 / Self::MAX_FEE_BPS as u128) as u64
+            }
+            Self::FEE_FLAT => self.fee.get(),
+            _ => 0,
+        };
+        if self.is_paid_by_claimer() {
+            fee
+        } else {
+            fee.min(amount)
+        }
+    }
+
+    pub fn is_paid_by_claimer(&self) -> bool {
+        self.fee_source[0] == Self::FEE_FROM_CLAIMER
+    }
+}
+
+/// The config and its treasury as every claim passes them, and the fee
+/// payment they imply.
+pub struct ProtocolFee<'info> {
+    /// Config PDA, possibly not created yet
+    pub config: &'info AccountInfo,
+    /// Receives the protocol fee; follows the config once it exists
+    pub treasury: Option<&'info AccountInfo>,
+}
+
+impl<'info> ProtocolFee<'info> {
+    /// Check `config` and, once it exists, the treasury at the start of
+    /// `remaining`. Returns the accounts left over.
+    ///
+    /// The config is always passed, so a fee cannot be skipped by leaving it
+    /// out.
+    pub fn parse(
+        config: &'info AccountInfo,
+        remaining: &'info [AccountInfo],
+    ) -> Result<(Self, &'info [AccountInfo]), ProgramError> {
+        check_config(config)?;
+        if config.data_is_empty() {
+            return Ok((
+                ProtocolFee {
+                    config,
+                    treasury: None,
+                },
+                remaining,
+            ));
+        }
+
+        let [treasury, remaining @ ..] = remaining else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        check_writable(treasury)?;
+        if Config::load(config)?.treasury.ne(treasury.key()) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok((
+            ProtocolFee {
+                config,
+                treasury: Some(treasury),
+            },
+            remaining,
+        ))
+    }
+
+    /// Protocol fee owed on a claim of `amount`, 0 until the config exists
+    pub fn fee_for(&self, amount: u64) -> Result<u64, ProgramError> {
+        if self.treasury.is_none() {
+            return Ok(0);
+        }
+        Ok(Config::load(self.config)?.fee_for(amount))
+    }
+
+    /// Move `amount` from `vault` to `recipient` and `fee` to the treasury,
+    /// either withheld from `amount` or paid by `payer` as the config says.
+    pub fn pay_out(
+        &self,
+        vault: &AccountInfo,
+        payer: &AccountInfo,
+        recipient: &AccountInfo,
+        amount: u64,
+        fee: u64,
+    ) -> ProgramResult {
+        Vault::debit(vault, amount)?;
+
+        let mut to_recipient = amount;
+        if let Some(treasury) = self.treasury.filter(|_| fee > 0) {
+            if Config::load(self.config)?.is_paid_by_claimer() {
+                pinocchio_system::instructions::Transfer {
+                    from: payer,
+                    to: treasury,
+                    lamports: fee,
+                }
+                .invoke()?;
+            } else {
+                to_recipient -= fee;
+                *treasury.try_borrow_mut_lamports()? += fee;
+            }
+        }
+
+        *recipient.try_borrow_mut_lamports()? += to_recipient;
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    utils::{AccountHeader, DataLen, PodU64, ZeroCopy},
+    validation::check_canonical_pda,
+};
+
+/// Optional description of a campaign, so explorers and claim UIs can find
+/// what it is and where its proofs are. `name` and `uri` are UTF-8, padded
+/// with zeros.
+#[repr(C)]
+pub struct Metadata {
+    /// `AccountHeader::DISCRIMINATOR` of this type
+    pub discriminator: [u8; 8],
+    /// Layout version, `AccountHeader::VERSION` for accounts this program writes
+    pub version: [u8; 1],
+    pub bump: [u8; 1],
+    pub name: [u8; Metadata::MAX_NAME_LEN],
+    /// Where the proof file is distributed
+    pub uri: [u8; Metadata::MAX_URI_LEN],
+    /// Content hash of the full tree file, to check a downloaded copy
+    pub tree_hash: [u8; 32],
+    pub leaf_count: PodU64,
+    pub depth: [u8; 1],
+}
+
+impl DataLen for Metadata {
+    const LEN: usize = core::mem::size_of::<Metadata>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for Metadata {}
+
+impl AccountHeader for Metadata {
+    const DISCRIMINATOR: [u8; 8] = [72, 11, 121, 26, 111, 181, 85, 93];
+    const VERSION: u8 = 1;
+}
+
+impl Metadata {
+    pub const SEED: &'static [u8] = b"metadata";
+
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const MAX_URI_LEN: usize = 200;
+
+    /// Check that `target` is the metadata PDA of `airdrop` at its canonical
+    /// bump
+    pub fn validate_pda(target: &Pubkey, airdrop: &Pubkey, bump: u8) -> Result<(), ProgramError> {
+        check_canonical_pda(target, &[Self::SEED, airdrop.as_ref()], bump)
+    }
+}
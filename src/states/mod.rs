@@ -3,3 +3,28 @@ pub use airdrop::*;
 
 pub mod claim_status;
 pub use claim_status::*;
+
+pub mod config;
+pub use config::*;
+
+pub mod metadata;
+pub use metadata::*;
+
+pub mod proof_buffer;
+pub use proof_buffer::*;
+
+pub mod redirect;
+pub use redirect::*;
+
+pub mod vault;
+pub use vault::*;
+
+#[cfg(feature = "zk")]
+pub mod nullifier;
+#[cfg(feature = "zk")]
+pub use nullifier::*;
+
+#[cfg(feature = "zk")]
+pub mod zk_config;
+#[cfg(feature = "zk")]
+pub use zk_config::*;
@@ -0,0 +1,48 @@
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::check_canonical_pda,
+};
+
+/// Marks a nullifier of an anonymous campaign as spent. Takes the place of
+/// `ClaimStatus`, which would link the claim to a recipient.
+#[repr(C)]
+pub struct Nullifier {
+    /// `AccountHeader::DISCRIMINATOR` of this type
+    pub discriminator: [u8; 8],
+    /// Layout version, `AccountHeader::VERSION` for accounts this program writes
+    pub version: [u8; 1],
+    pub bump: [u8; 1],
+}
+
+impl DataLen for Nullifier {
+    const LEN: usize = core::mem::size_of::<Nullifier>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for Nullifier {}
+
+impl AccountHeader for Nullifier {
+    const DISCRIMINATOR: [u8; 8] = [18, 56, 142, 165, 181, 158, 187, 133];
+    const VERSION: u8 = 1;
+}
+
+impl Nullifier {
+    pub const SEED: &'static [u8] = b"nullifier";
+
+    /// Check that `target` is the nullifier PDA at its canonical bump, so a
+    /// nullifier hash has exactly one address to be spent at
+    pub fn validate_pda(
+        target: &Pubkey,
+        airdrop: &Pubkey,
+        nullifier_hash: &[u8; 32],
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        check_canonical_pda(
+            target,
+            &[Self::SEED, airdrop.as_ref(), nullifier_hash.as_ref()],
+            bump,
+        )
+    }
+}
@@ -0,0 +1,69 @@
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    utils::{proof_from_bytes, AccountHeader, DataLen, ZeroCopy},
+    validation::check_canonical_pda,
+};
+
+/// Scratch account holding a merkle proof that is too long to fit in a single
+/// claim transaction. The header is followed by `proof_len` 32-byte elements.
+#[repr(C)]
+pub struct ProofBuffer {
+    /// `AccountHeader::DISCRIMINATOR` of this type
+    pub discriminator: [u8; 8],
+    /// Layout version, `AccountHeader::VERSION` for accounts this program writes
+    pub version: [u8; 1],
+    pub bump: [u8; 1],
+    /// Number of proof elements the buffer was sized for
+    pub proof_len: [u8; 1],
+}
+
+impl DataLen for ProofBuffer {
+    const LEN: usize = core::mem::size_of::<ProofBuffer>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for ProofBuffer {}
+
+impl AccountHeader for ProofBuffer {
+    const DISCRIMINATOR: [u8; 8] = [71, 133, 225, 94, 9, 130, 40, 161];
+    const VERSION: u8 = 1;
+}
+
+impl ProofBuffer {
+    pub const SEED: &'static [u8] = b"proof_buffer";
+
+    pub fn space(proof_len: u8) -> usize {
+        Self::LEN + proof_len as usize * 32
+    }
+
+    /// Check that `target` is the proof buffer of `claimer` at its canonical
+    /// bump
+    pub fn validate_pda(
+        target: &Pubkey,
+        airdrop: &Pubkey,
+        claimer: &Pubkey,
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        check_canonical_pda(
+            target,
+            &[Self::SEED, airdrop.as_ref(), claimer.as_ref()],
+            bump,
+        )
+    }
+
+    /// Split raw account data into the header and the staged proof elements.
+    pub fn unpack(data: &[u8]) -> Result<(&ProofBuffer, &[[u8; 32]]), ProgramError> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, proof_bytes) = data.split_at(Self::LEN);
+        let header = ProofBuffer::from_bytes(header)?;
+        let proof_len = header.proof_len[0] as usize;
+        if proof_bytes.len() != proof_len * 32 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let proof = proof_from_bytes(proof_bytes).ok_or(ProgramError::InvalidAccountData)?;
+        Ok((header, proof))
+    }
+}
@@ -0,0 +1,97 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    utils::{AccountHeader, DataLen, ZeroCopy},
+    validation::{check_canonical_pda, check_program_owned},
+};
+
+/// Moves the leaves of `old_recipient` to `new_recipient`, for recipients that
+/// lost their key. The leaf and its claim status stay keyed by the old pubkey,
+/// and only the new one may claim them.
+#[repr(C)]
+pub struct Redirect {
+    /// `AccountHeader::DISCRIMINATOR` of this type
+    pub discriminator: [u8; 8],
+    /// Layout version, `AccountHeader::VERSION` for accounts this program writes
+    pub version: [u8; 1],
+    pub bump: [u8; 1],
+    pub old_recipient: Pubkey,
+    pub new_recipient: Pubkey,
+    /// 1 if `old_recipient` co-signed the last `ReassignLeaf`, 0 if the
+    /// authority reassigned the leaves alone
+    pub signed_by_old_recipient: [u8; 1],
+}
+
+impl DataLen for Redirect {
+    const LEN: usize = core::mem::size_of::<Redirect>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for Redirect {}
+
+impl AccountHeader for Redirect {
+    const DISCRIMINATOR: [u8; 8] = [62, 64, 178, 163, 12, 198, 213, 108];
+    const VERSION: u8 = 1;
+}
+
+impl Redirect {
+    pub const SEED: &'static [u8] = b"redirect";
+
+    /// Check that `target` is the redirect of `old_recipient` at its
+    /// canonical bump, so a recipient has exactly one redirect address
+    pub fn validate_pda(
+        target: &Pubkey,
+        airdrop: &Pubkey,
+        old_recipient: &Pubkey,
+        bump: u8,
+    ) -> Result<(), ProgramError> {
+        check_canonical_pda(
+            target,
+            &[Self::SEED, airdrop.as_ref(), old_recipient.as_ref()],
+            bump,
+        )
+    }
+
+    /// Pubkey committed in the leaves that pay out to `recipient`. `redirect`
+    /// is either an existing redirect to `recipient`, or the redirect PDA of
+    /// `recipient` itself, which must be empty. Returns `None` when that PDA
+    /// holds a redirect elsewhere: the leaves of a reassigned key can only be
+    /// claimed by the new one.
+    pub fn leaf_pubkey(
+        redirect: &AccountInfo,
+        airdrop: &Pubkey,
+        recipient: &Pubkey,
+    ) -> Result<Option<Pubkey>, ProgramError> {
+        if redirect.data_is_empty() {
+            let (expected, _) = pubkey::find_program_address(
+                &[Self::SEED, airdrop.as_ref(), recipient.as_ref()],
+                &crate::ID,
+            );
+            if expected.ne(redirect.key()) {
+                return Err(AirdropProgramError::InvalidPda.into());
+            }
+            return Ok(Some(*recipient));
+        }
+
+        check_program_owned(redirect)?;
+        let redirect_state = Self::load(redirect)?;
+        Self::validate_pda(
+            redirect.key(),
+            airdrop,
+            &redirect_state.old_recipient,
+            redirect_state.bump[0],
+        )?;
+        if redirect_state.new_recipient.eq(recipient) {
+            Ok(Some(redirect_state.old_recipient))
+        } else if redirect_state.old_recipient.eq(recipient) {
+            Ok(None)
+        } else {
+            Err(AirdropProgramError::Unauthorized.into())
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::{errors::AirdropProgramError, validation::check_canonical_pda};
+
+/// Program-owned PDA without data that holds the distributable funds of a
+/// campaign, so the state account only ever holds its own rent. Every
+/// deposit, claim and clawback moves lamports in or out of it.
+pub struct Vault;
+
+impl Vault {
+    pub const SEED: &'static [u8] = b"vault";
+
+    /// Check that `target` is the vault of `airdrop` at its canonical bump
+    pub fn validate_pda(target: &Pubkey, airdrop: &Pubkey, bump: u8) -> Result<(), ProgramError> {
+        check_canonical_pda(target, &[Self::SEED, airdrop.as_ref()], bump)
+    }
+
+    /// Creates the vault at the PDA with bump `bump`, holding `lamports`.
+    /// Anyone can send lamports to the PDA before it exists, and
+    /// `CreateAccount` fails on such an account, so a funded system account
+    /// is topped up and taken over instead.
+    pub fn create(
+        payer: &AccountInfo,
+        vault: &AccountInfo,
+        airdrop: &Pubkey,
+        bump: u8,
+        lamports: u64,
+    ) -> ProgramResult {
+        if !vault.is_owned_by(&pinocchio_system::ID) || !vault.data_is_empty() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        let bump_binding = [bump];
+        let seed = [
+            Seed::from(Self::SEED),
+            Seed::from(airdrop.as_ref()),
+            Seed::from(&bump_binding),
+        ];
+
+        let prefunded = vault.lamports();
+        if prefunded == 0 {
+            return pinocchio_system::instructions::CreateAccount {
+                from: payer,
+                to: vault,
+                space: 0,
+                lamports,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&[Signer::from(&seed)]);
+        }
+
+        if prefunded < lamports {
+            pinocchio_system::instructions::Transfer {
+                from: payer,
+                to: vault,
+                lamports: lamports - prefunded,
+            }
+            .invoke()?;
+        }
+        pinocchio_system::instructions::Allocate {
+            account: vault,
+            space: 0,
+        }
+        .invoke_signed(&[Signer::from(&seed)])?;
+        pinocchio_system::instructions::Assign {
+            account: vault,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&[Signer::from(&seed)])
+    }
+
+    /// Takes `amount` out of the vault, failing instead of dipping into the
+    /// rent it needs to stay alive. The caller credits the lamports.
+    pub fn debit(vault: &AccountInfo, amount: u64) -> ProgramResult {
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        let mut lamports = vault.try_borrow_mut_lamports()?;
+        let remaining = lamports
+            .checked_sub(amount)
+            .filter(|remaining| *remaining >= rent_exempt)
+            .ok_or(AirdropProgramError::InsufficientFunds)?;
+        *lamports = remaining;
+        Ok(())
+    }
+}
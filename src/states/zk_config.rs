@@ -0,0 +1,57 @@
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    utils::{AccountHeader, DataLen, PodU64, ZeroCopy, ZK_PUBLIC_INPUTS},
+    validation::check_canonical_pda,
+};
+
+/// Maximum number of fixed-amount tiers of an anonymous campaign
+pub const MAX_ZK_TIERS: usize = 4;
+
+/// Groth16 verifying key and tier amounts of an anonymous campaign. Points are
+/// uncompressed and big-endian, as the `alt_bn128` syscalls expect them.
+#[repr(C)]
+pub struct ZkConfig {
+    /// `AccountHeader::DISCRIMINATOR` of this type
+    pub discriminator: [u8; 8],
+    /// Layout version, `AccountHeader::VERSION` for accounts this program writes
+    pub version: [u8; 1],
+    pub bump: [u8; 1],
+    pub tier_count: [u8; 1],
+    /// Amount paid per claim of each tier, little-endian
+    pub tier_amounts: [PodU64; MAX_ZK_TIERS],
+    pub vk_alpha_g1: [u8; 64],
+    pub vk_beta_g2: [u8; 128],
+    pub vk_gamma_g2: [u8; 128],
+    pub vk_delta_g2: [u8; 128],
+    pub vk_ic: [[u8; 64]; ZK_PUBLIC_INPUTS + 1],
+}
+
+impl DataLen for ZkConfig {
+    const LEN: usize = core::mem::size_of::<ZkConfig>();
+}
+
+// SAFETY: byte arrays only, so no padding and alignment 1
+unsafe impl ZeroCopy for ZkConfig {}
+
+impl AccountHeader for ZkConfig {
+    const DISCRIMINATOR: [u8; 8] = [181, 176, 242, 167, 108, 219, 13, 202];
+    const VERSION: u8 = 1;
+}
+
+impl ZkConfig {
+    pub const SEED: &'static [u8] = b"zk_config";
+
+    /// Check that `target` is the verifying-key PDA of `airdrop` at its
+    /// canonical bump
+    pub fn validate_pda(target: &Pubkey, airdrop: &Pubkey, bump: u8) -> Result<(), ProgramError> {
+        check_canonical_pda(target, &[Self::SEED, airdrop.as_ref()], bump)
+    }
+
+    pub fn tier_amount(&self, tier: u8) -> Option<u64> {
+        if tier >= self.tier_count[0] {
+            return None;
+        }
+        Some(self.tier_amounts[tier as usize].get())
+    }
+}
@@ -1,52 +1,488 @@
-use pinocchio::program_error::ProgramError;
-use solana_nostd_keccak::hash;
+use pinocchio::{
+    account_info::{AccountInfo, Ref, RefMut},
+    program_error::ProgramError,
+};
+
+use crate::errors::AirdropProgramError;
+
 pub trait DataLen {
     const LEN: usize;
 }
 
-#[inline(always)]
-pub unsafe fn load_acc_unchecked<T: DataLen>(bytes: &[u8]) -> Result<&T, ProgramError> {
-    if bytes.len() != T::LEN {
-        return Err(ProgramError::InvalidAccountData);
+/// Plain data that can be viewed in place inside an account or instruction
+/// data buffer, without copying.
+///
+/// # Safety
+///
+/// The implementor must be `#[repr(C)]` (or `#[repr(C, packed)]`) with no
+/// padding, every byte pattern must be a valid value, and `LEN` must be its
+/// size. Alignment is checked at compile time: every field must have
+/// alignment 1, which is why integers are stored as `[u8; N]` or `PodU64`.
+pub unsafe trait ZeroCopy: DataLen + Sized {
+    #[doc(hidden)]
+    const LAYOUT_CHECK: () = assert!(
+        core::mem::align_of::<Self>() == 1 && core::mem::size_of::<Self>() == Self::LEN,
+        "ZeroCopy types must have alignment 1 and size LEN"
+    );
+
+    /// View `bytes` as `Self`, if it has exactly the right length.
+    #[inline(always)]
+    fn ref_from(bytes: &[u8]) -> Option<&Self> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::LAYOUT_CHECK;
+        if bytes.len() != Self::LEN {
+            return None;
+        }
+        // SAFETY: the length matches, alignment is 1 and any byte pattern is
+        // a valid `Self`
+        Some(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// View `bytes` as a mutable `Self`, if it has exactly the right length.
+    #[inline(always)]
+    fn mut_from(bytes: &mut [u8]) -> Option<&mut Self> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::LAYOUT_CHECK;
+        if bytes.len() != Self::LEN {
+            return None;
+        }
+        // SAFETY: as in `ref_from`; the exclusive borrow of `bytes` makes
+        // this the only reference
+        Some(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// View `bytes` as a slice of `Self`, if its length is a multiple of `LEN`.
+    #[inline(always)]
+    fn slice_from(bytes: &[u8]) -> Option<&[Self]> {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::LAYOUT_CHECK;
+        if Self::LEN == 0 || !bytes.len().is_multiple_of(Self::LEN) {
+            return None;
+        }
+        // SAFETY: as in `ref_from`, for each of the `bytes.len() / LEN` items
+        Some(unsafe {
+            core::slice::from_raw_parts(bytes.as_ptr() as *const Self, bytes.len() / Self::LEN)
+        })
+    }
+
+    /// Copy `Self` out of `bytes`, if it has exactly the right length.
+    #[inline(always)]
+    fn read_from(bytes: &[u8]) -> Option<Self> {
+        // SAFETY: `ref_from` checked the length and the type is plain data
+        Self::ref_from(bytes).map(|value| unsafe { core::ptr::read(value) })
     }
-    Ok(&*(bytes.as_ptr() as *const T))
 }
 
+/// View `bytes` as merkle proof elements, if its length is a multiple of 32.
 #[inline(always)]
-pub unsafe fn load_acc_mut_unchecked<T: DataLen>(bytes: &mut [u8]) -> Result<&mut T, ProgramError> {
-    if bytes.len() != T::LEN {
-        return Err(ProgramError::InvalidAccountData);
+pub fn proof_from_bytes(bytes: &[u8]) -> Option<&[[u8; 32]]> {
+    if !bytes.len().is_multiple_of(32) {
+        return None;
+    }
+    // SAFETY: `[u8; 32]` has alignment 1 and the length was checked
+    Some(unsafe {
+        core::slice::from_raw_parts(bytes.as_ptr() as *const [u8; 32], bytes.len() / 32)
+    })
+}
+
+/// Little-endian u64 with alignment 1, for zero-copy layouts
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct PodU64(pub [u8; 8]);
+
+impl PodU64 {
+    #[inline(always)]
+    pub const fn get(self) -> u64 {
+        u64::from_le_bytes(self.0)
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, value: u64) {
+        self.0 = value.to_le_bytes();
+    }
+}
+
+impl From<u64> for PodU64 {
+    fn from(value: u64) -> Self {
+        Self(value.to_le_bytes())
+    }
+}
+
+impl From<PodU64> for u64 {
+    fn from(value: PodU64) -> Self {
+        value.get()
+    }
+}
+
+/// Length of the header every state account starts with: the 8-byte
+/// discriminator, then the version byte.
+pub const ACCOUNT_HEADER_LEN: usize = 9;
+
+/// State account type. Its first fields must be `discriminator: [u8; 8]` and
+/// `version: [u8; 1]`, so a load can tell it apart from any other account of
+/// the program that happens to have the same size.
+///
+/// `load` and `load_mut` go through the account's borrow state, so a second
+/// mutable view of the same account fails instead of aliasing.
+pub trait AccountHeader: ZeroCopy {
+    /// First 8 bytes of `sha256("account:<TypeName>")`, as Anchor derives them
+    const DISCRIMINATOR: [u8; 8];
+    /// Current layout version
+    const VERSION: u8;
+
+    /// View account data as `Self`, checking its length and header.
+    #[inline(always)]
+    fn from_bytes(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        let value = Self::ref_from(bytes).ok_or(ProgramError::InvalidAccountData)?;
+        check_account_header::<Self>(bytes)?;
+        Ok(value)
+    }
+
+    /// View account data as a mutable `Self`, checking its length and header.
+    #[inline(always)]
+    fn from_bytes_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        check_account_header::<Self>(bytes)?;
+        Self::mut_from(bytes).ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// Write the header of `Self` into freshly created account data and view
+    /// it as a mutable `Self`. Fails if the data already carries a header.
+    #[inline(always)]
+    fn init_bytes(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if bytes[..ACCOUNT_HEADER_LEN].iter().any(|byte| *byte != 0) {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        bytes[..8].copy_from_slice(&Self::DISCRIMINATOR);
+        bytes[8] = Self::VERSION;
+        Self::mut_from(bytes).ok_or(ProgramError::InvalidAccountData)
+    }
+
+    /// Borrow `account`'s data as `Self`.
+    #[inline(always)]
+    fn load(account: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        let data = account.try_borrow_data()?;
+        Self::from_bytes(&data)?;
+        Ref::filter_map(data, Self::ref_from).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Borrow `account`'s data mutably as `Self`.
+    #[inline(always)]
+    fn load_mut(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        let mut data = account.try_borrow_mut_data()?;
+        Self::from_bytes_mut(&mut data)?;
+        RefMut::filter_map(data, Self::mut_from).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Write the header of `Self` into a freshly created `account` and borrow
+    /// it mutably.
+    #[inline(always)]
+    fn init_mut(account: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        let mut data = account.try_borrow_mut_data()?;
+        Self::init_bytes(&mut data)?;
+        RefMut::filter_map(data, Self::mut_from).map_err(|_| ProgramError::InvalidAccountData)
     }
-    Ok(&mut *(bytes.as_mut_ptr() as *mut T))
 }
 
+/// Check that `bytes` starts with the header of the current layout of `T`.
 #[inline(always)]
-pub unsafe fn load_ix_data<T: DataLen>(bytes: &[u8]) -> Result<&T, ProgramError> {
-    if bytes.len() != T::LEN {
-        return Err(ProgramError::InvalidInstructionData);
+pub fn check_account_header<T: AccountHeader>(bytes: &[u8]) -> Result<(), ProgramError> {
+    if bytes.len() < ACCOUNT_HEADER_LEN || bytes[..8] != T::DISCRIMINATOR {
+        return Err(AirdropProgramError::InvalidAccountDiscriminator.into());
+    }
+    if bytes[8] != T::VERSION {
+        return Err(AirdropProgramError::UnsupportedAccountVersion.into());
     }
-    Ok(&*(bytes.as_ptr() as *const T))
+    Ok(())
 }
 
+/// View `T` as its raw bytes.
+///
+/// # Safety
+///
+/// `T` must not contain padding bytes.
 pub unsafe fn to_bytes<T: DataLen>(data: &T) -> &[u8] {
     core::slice::from_raw_parts(data as *const T as *const u8, T::LEN)
 }
 
+/// View `T` as its raw mutable bytes.
+///
+/// # Safety
+///
+/// `T` must not contain padding bytes and every byte pattern must be a valid `T`.
 pub unsafe fn to_mut_bytes<T: DataLen>(data: &mut T) -> &mut [u8] {
     core::slice::from_raw_parts_mut(data as *mut T as *mut u8, T::LEN)
 }
 
-/// Helper function to hash two 32-byte arrays together
+/// Hash function used for the leaves and nodes of a campaign's tree. Every
+/// variant other than Keccak-256 is only available with its cargo feature.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum HashFunction {
+    Keccak256 = 0,
+    /// `sol_sha256` syscall
+    Sha256 = 1,
+    /// `sol_poseidon` syscall over BN254, big-endian field elements
+    Poseidon = 2,
+}
+
+impl TryFrom<u8> for HashFunction {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            #[cfg(feature = "keccak")]
+            0 => Ok(Self::Keccak256),
+            #[cfg(feature = "sha256")]
+            1 => Ok(Self::Sha256),
+            #[cfg(feature = "poseidon")]
+            2 => Ok(Self::Poseidon),
+            _ => Err(AirdropProgramError::UnsupportedHashFunction.into()),
+        }
+    }
+}
+
+impl HashFunction {
+    /// Hash the concatenation of `vals`; for Poseidon every value is one field
+    /// element. Returns `None` when the input is rejected (Poseidon inputs
+    /// above the field modulus) or the hash function is not compiled in.
+    #[allow(unused_variables)]
+    pub fn hashv(self, vals: &[&[u8]]) -> Option<[u8; 32]> {
+        match self {
+            #[cfg(feature = "keccak")]
+            Self::Keccak256 => Some(solana_nostd_keccak::hashv(vals)),
+            #[cfg(feature = "sha256")]
+            Self::Sha256 => Some(sha256v(vals)),
+            #[cfg(feature = "poseidon")]
+            Self::Poseidon => poseidonv(vals),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Hash two 32-byte nodes together
+    #[inline(always)]
+    pub fn hash_pair(self, left: &[u8; 32], right: &[u8; 32]) -> Option<[u8; 32]> {
+        self.hashv(&[left, right])
+    }
+
+    /// Create a leaf hash from recipient address and amount. Poseidon cannot
+    /// take a pubkey as a single field element, so it hashes the two 16-byte
+    /// halves and left-pads every input to 32 bytes.
+    pub fn create_airdrop_leaf(
+        self,
+        recipient: &[u8; 32],
+        amount: u64,
+        is_claimed: u8,
+    ) -> Option<[u8; 32]> {
+        match self {
+            Self::Poseidon => {
+                let mut recipient_hi = [0u8; 32];
+                let mut recipient_lo = [0u8; 32];
+                let mut amount_be = [0u8; 32];
+                let mut is_claimed_be = [0u8; 32];
+                recipient_hi[16..].copy_from_slice(&recipient[..16]);
+                recipient_lo[16..].copy_from_slice(&recipient[16..]);
+                amount_be[24..].copy_from_slice(&amount.to_be_bytes());
+                is_claimed_be[31] = is_claimed;
+                self.hashv(&[&recipient_hi, &recipient_lo, &amount_be, &is_claimed_be])
+            }
+            _ => self.hashv(&[recipient, &amount.to_le_bytes(), &[is_claimed]]),
+        }
+    }
+
+    /// Create a leaf hash committing to both the account that receives the
+    /// funds and the key that must sign the claim, for campaigns in
+    /// `MODE_CLAIM_AUTHORITY`. Poseidon hashes both pubkeys as halves.
+    pub fn create_delegated_airdrop_leaf(
+        self,
+        beneficiary: &[u8; 32],
+        claim_authority: &[u8; 32],
+        amount: u64,
+        is_claimed: u8,
+    ) -> Option<[u8; 32]> {
+        match self {
+            Self::Poseidon => {
+                let mut beneficiary_hi = [0u8; 32];
+                let mut beneficiary_lo = [0u8; 32];
+                let mut claim_authority_hi = [0u8; 32];
+                let mut claim_authority_lo = [0u8; 32];
+                let mut amount_be = [0u8; 32];
+                let mut is_claimed_be = [0u8; 32];
+                beneficiary_hi[16..].copy_from_slice(&beneficiary[..16]);
+                beneficiary_lo[16..].copy_from_slice(&beneficiary[16..]);
+                claim_authority_hi[16..].copy_from_slice(&claim_authority[..16]);
+                claim_authority_lo[16..].copy_from_slice(&claim_authority[16..]);
+                amount_be[24..].copy_from_slice(&amount.to_be_bytes());
+                is_claimed_be[31] = is_claimed;
+                self.hashv(&[
+                    &beneficiary_hi,
+                    &beneficiary_lo,
+                    &claim_authority_hi,
+                    &claim_authority_lo,
+                    &amount_be,
+                    &is_claimed_be,
+                ])
+            }
+            _ => self.hashv(&[
+                beneficiary,
+                claim_authority,
+                &amount.to_le_bytes(),
+                &[is_claimed],
+            ]),
+        }
+    }
+
+    /// Create a leaf hash from an EVM address and amount. Poseidon takes the
+    /// 20-byte address as one left-padded field element.
+    pub fn create_evm_airdrop_leaf(
+        self,
+        address: &[u8; 20],
+        amount: u64,
+        is_claimed: u8,
+    ) -> Option<[u8; 32]> {
+        match self {
+            Self::Poseidon => {
+                let mut address_be = [0u8; 32];
+                let mut amount_be = [0u8; 32];
+                let mut is_claimed_be = [0u8; 32];
+                address_be[12..].copy_from_slice(address);
+                amount_be[24..].copy_from_slice(&amount.to_be_bytes());
+                is_claimed_be[31] = is_claimed;
+                self.hashv(&[&address_be, &amount_be, &is_claimed_be])
+            }
+            _ => self.hashv(&[address, &amount.to_le_bytes(), &[is_claimed]]),
+        }
+    }
+}
+
+/// Recover the EVM address that produced `signature` over `message_hash`.
+/// Accepts both `0/1` and Ethereum-style `27/28` recovery ids.
+#[cfg(feature = "evm")]
+pub fn recover_evm_address(
+    message_hash: &[u8; 32],
+    recovery_id: u8,
+    signature: &[u8; 64],
+) -> Option<[u8; 20]> {
+    let recovery_id = if recovery_id >= 27 {
+        recovery_id - 27
+    } else {
+        recovery_id
+    };
+    if recovery_id > 1 {
+        return None;
+    }
+
+    #[cfg(target_os = "solana")]
+    let public_key = {
+        let mut public_key = [0u8; 64];
+        let result = unsafe {
+            pinocchio::syscalls::sol_secp256k1_recover(
+                message_hash.as_ptr(),
+                recovery_id as u64,
+                signature.as_ptr(),
+                public_key.as_mut_ptr(),
+            )
+        };
+        if result != 0 {
+            return None;
+        }
+        public_key
+    };
+    #[cfg(not(target_os = "solana"))]
+    let public_key = {
+        let message = libsecp256k1::Message::parse(message_hash);
+        let signature = libsecp256k1::Signature::parse_standard_slice(signature).ok()?;
+        let recovery_id = libsecp256k1::RecoveryId::parse(recovery_id).ok()?;
+        let public_key = libsecp256k1::recover(&message, &signature, &recovery_id).ok()?;
+        let mut uncompressed = [0u8; 64];
+        uncompressed.copy_from_slice(&public_key.serialize()[1..]);
+        uncompressed
+    };
+
+    // the address is the last 20 bytes of the keccak of the uncompressed key
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&solana_nostd_keccak::hash(&public_key)[12..]);
+    Some(address)
+}
+
+#[cfg(feature = "sha256")]
+fn sha256v(vals: &[&[u8]]) -> [u8; 32] {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash_result = [0u8; 32];
+        unsafe {
+            pinocchio::syscalls::sol_sha256(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash_result.as_mut_ptr(),
+            );
+        }
+        hash_result
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        for val in vals {
+            hasher.update(val);
+        }
+        hasher.finalize().into()
+    }
+}
+
+#[cfg(feature = "poseidon")]
+fn poseidonv(vals: &[&[u8]]) -> Option<[u8; 32]> {
+    #[cfg(target_os = "solana")]
+    {
+        // parameters 0 = BN254 x^5, endianness 0 = big-endian
+        let mut hash_result = [0u8; 32];
+        let result = unsafe {
+            pinocchio::syscalls::sol_poseidon(
+                0,
+                0,
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash_result.as_mut_ptr(),
+            )
+        };
+        (result == 0).then_some(hash_result)
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        use ark_bn254::Fr;
+        use light_poseidon::{Poseidon, PoseidonBytesHasher};
+
+        Poseidon::<Fr>::new_circom(vals.len())
+            .ok()?
+            .hash_bytes_be(vals)
+            .ok()
+    }
+}
+
+/// Helper function to hash two 32-byte arrays together with Keccak-256
+#[cfg(feature = "keccak")]
 #[inline(always)]
 pub fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
     let mut hash_input = [0u8; 64];
     hash_input[..32].copy_from_slice(left);
     hash_input[32..].copy_from_slice(right);
-    hash(&hash_input)
+    solana_nostd_keccak::hash(&hash_input)
 }
 
+/// Longest proof a claim accepts: a u64 leaf index addresses at most 64
+/// levels, so a longer proof can never verify
+pub const MAX_PROOF_LEN: usize = 64;
+
 /// Optimized helper function to verify Merkle proof
 pub fn verify_merkle_proof(
+    hash_fn: HashFunction,
     leaf: &[u8; 32],
     proof: &[[u8; 32]],
     leaf_index: u64,
@@ -54,32 +490,290 @@ pub fn verify_merkle_proof(
 ) -> bool {
     // Early return for empty proof
     if proof.is_empty() {
-        return leaf == expected_root;
+        return leaf_index == 0 && leaf == expected_root;
     }
 
     let mut computed_hash = *leaf;
     let mut index = leaf_index;
 
     for proof_element in proof.iter() {
-        computed_hash = if index & 1 == 0 {
+        let parent = if index & 1 == 0 {
             // Current node is left child
-            hash_pair(&computed_hash, proof_element)
+            hash_fn.hash_pair(&computed_hash, proof_element)
         } else {
             // Current node is right child
-            hash_pair(proof_element, &computed_hash)
+            hash_fn.hash_pair(proof_element, &computed_hash)
         };
+        let Some(parent) = parent else {
+            return false;
+        };
+        computed_hash = parent;
         index >>= 1; // Equivalent to index /= 2 but faster
     }
 
-    computed_hash == *expected_root
+    // bits above the tree depth would give the same leaf another index, and
+    // so another claim status when statuses are keyed by leaf index
+    index == 0 && computed_hash == *expected_root
 }
 
-/// Create a leaf hash from recipient address and amount
+/// Maximum number of leaves a single multiproof can verify
+pub const MAX_MULTIPROOF_LEAVES: usize = 16;
+
+/// Verify several leaves against the root at once, OpenZeppelin multiproof
+/// style. `leaves` must be sorted by strictly increasing `leaf_indices`; each
+/// flag says whether the next hash pairs two known nodes (`true`) or a known
+/// node with the next `proof` element (`false`). Since this tree hashes by
+/// position, every queued node carries its index to pick the hashing side.
+pub fn verify_merkle_multiproof(
+    hash_fn: HashFunction,
+    leaves: &[[u8; 32]],
+    leaf_indices: &[u64],
+    proof: &[[u8; 32]],
+    proof_flags: &[bool],
+    expected_root: &[u8; 32],
+) -> bool {
+    let leaves_len = leaves.len();
+    if leaves_len == 0 || leaves_len > MAX_MULTIPROOF_LEAVES || leaves_len != leaf_indices.len() {
+        return false;
+    }
+    if leaves_len + proof.len() != proof_flags.len() + 1 {
+        return false;
+    }
+    if leaf_indices.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return false;
+    }
+
+    // Early return for a lone leaf without proof; as in `verify_merkle_proof`
+    // the root leaf only has index 0
+    if proof_flags.is_empty() {
+        return leaf_indices[0] == 0 && leaves[0] == *expected_root;
+    }
+
+    // FIFO of computed (hash, index, level); at most one entry per leaf is
+    // pending at any time, so a ring of MAX_MULTIPROOF_LEAVES is enough
+    let mut queue = [([0u8; 32], 0u64, 0u8); MAX_MULTIPROOF_LEAVES];
+    let (mut head, mut len) = (0usize, 0usize);
+    let mut leaf_pos = 0;
+    let mut proof_pos = 0;
+
+    let mut next_node = |queue: &[([u8; 32], u64, u8)], head: &mut usize, len: &mut usize| {
+        if leaf_pos < leaves_len {
+            leaf_pos += 1;
+            Some((leaves[leaf_pos - 1], leaf_indices[leaf_pos - 1], 0u8))
+        } else if *len > 0 {
+            let node = queue[*head];
+            *head = (*head + 1) % MAX_MULTIPROOF_LEAVES;
+            *len -= 1;
+            Some(node)
+        } else {
+            None
+        }
+    };
+
+    for &flag in proof_flags {
+        let Some((node, index, level)) = next_node(&queue, &mut head, &mut len) else {
+            return false;
+        };
+
+        let parent = if flag {
+            let Some((sibling, sibling_index, sibling_level)) =
+                next_node(&queue, &mut head, &mut len)
+            else {
+                return false;
+            };
+            // two known nodes must be the left and right child of one parent
+            if sibling_level != level || index & 1 != 0 || sibling_index != index + 1 {
+                return false;
+            }
+            hash_fn.hash_pair(&node, &sibling)
+        } else {
+            let Some(sibling) = proof.get(proof_pos) else {
+                return false;
+            };
+            proof_pos += 1;
+            if index & 1 == 0 {
+                hash_fn.hash_pair(&node, sibling)
+            } else {
+                hash_fn.hash_pair(sibling, &node)
+            }
+        };
+        let Some(parent) = parent else {
+            return false;
+        };
+
+        queue[(head + len) % MAX_MULTIPROOF_LEAVES] = (parent, index >> 1, level + 1);
+        len += 1;
+    }
+
+    // everything must be consumed, leaving only the root
+    if leaf_pos != leaves_len || proof_pos != proof.len() || len != 1 {
+        return false;
+    }
+    let (root, index, _) = queue[head];
+    index == 0 && root == *expected_root
+}
+
+/// Create a Keccak-256 leaf hash from recipient address and amount
+#[cfg(feature = "keccak")]
 #[inline(always)]
 pub fn create_airdrop_leaf(recipient: &[u8; 32], amount: u64, is_claimed: u8) -> [u8; 32] {
     let mut hash_input = [0u8; 41]; // 32 bytes for address + 8 bytes for amount + 1 byte for is_claimed
     hash_input[..32].copy_from_slice(recipient);
     hash_input[32..40].copy_from_slice(&amount.to_le_bytes());
     hash_input[40] = is_claimed;
-    hash(&hash_input)
+    solana_nostd_keccak::hash(&hash_input)
+}
+
+/// Number of public inputs of the anonymous-claim circuit:
+/// `[merkle_root, nullifier_hash, tier, destination_hi, destination_lo]`
+#[cfg(feature = "zk")]
+pub const ZK_PUBLIC_INPUTS: usize = 5;
+
+/// Order of the BN254 scalar field; public inputs must be reduced below it
+#[cfg(feature = "zk")]
+const BN254_SCALAR_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/// Order of the BN254 base field, used to negate G1 points
+#[cfg(feature = "zk")]
+const BN254_BASE_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+#[cfg(feature = "zk")]
+const ALT_BN128_ADD: u64 = 0;
+#[cfg(feature = "zk")]
+const ALT_BN128_MUL: u64 = 2;
+#[cfg(feature = "zk")]
+const ALT_BN128_PAIRING: u64 = 3;
+
+#[cfg(feature = "zk")]
+fn alt_bn128_group_op<const N: usize>(op: u64, input: &[u8]) -> Option<[u8; N]> {
+    #[cfg(target_os = "solana")]
+    {
+        let mut result = [0u8; N];
+        let status = unsafe {
+            pinocchio::syscalls::sol_alt_bn128_group_op(
+                op,
+                input.as_ptr(),
+                input.len() as u64,
+                result.as_mut_ptr(),
+            )
+        };
+        (status == 0).then_some(result)
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        use solana_bn254::prelude::{
+            alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing,
+        };
+
+        let output = match op {
+            ALT_BN128_ADD => alt_bn128_addition(input),
+            ALT_BN128_MUL => alt_bn128_multiplication(input),
+            _ => alt_bn128_pairing(input),
+        }
+        .ok()?;
+        output.try_into().ok()
+    }
+}
+
+/// Add two G1 points, `alt_bn128` big-endian encoding.
+#[cfg(feature = "zk")]
+pub fn alt_bn128_add(p: &[u8; 64], q: &[u8; 64]) -> Option<[u8; 64]> {
+    let mut input = [0u8; 128];
+    input[..64].copy_from_slice(p);
+    input[64..].copy_from_slice(q);
+    alt_bn128_group_op(ALT_BN128_ADD, &input)
+}
+
+/// Multiply a G1 point by a big-endian scalar.
+#[cfg(feature = "zk")]
+pub fn alt_bn128_mul(p: &[u8; 64], scalar: &[u8; 32]) -> Option<[u8; 64]> {
+    let mut input = [0u8; 96];
+    input[..64].copy_from_slice(p);
+    input[64..].copy_from_slice(scalar);
+    alt_bn128_group_op(ALT_BN128_MUL, &input)
+}
+
+/// Negate a G1 point: `(x, y) -> (x, q - y)`, the identity maps to itself.
+#[cfg(feature = "zk")]
+pub fn alt_bn128_negate(p: &[u8; 64]) -> [u8; 64] {
+    let mut negated = *p;
+    if p[32..] == [0u8; 32] {
+        return negated;
+    }
+    let mut borrow = 0u16;
+    for i in (0..32).rev() {
+        let lhs = BN254_BASE_MODULUS[i] as u16;
+        let rhs = p[32 + i] as u16 + borrow;
+        negated[32 + i] = lhs.wrapping_sub(rhs) as u8;
+        borrow = (lhs < rhs) as u16;
+    }
+    negated
+}
+
+/// Encode a field element: a 16-byte half of a pubkey or a small integer,
+/// left-padded big-endian.
+#[cfg(feature = "zk")]
+pub fn zk_field_element(bytes: &[u8]) -> [u8; 32] {
+    let mut element = [0u8; 32];
+    element[32 - bytes.len()..].copy_from_slice(bytes);
+    element
+}
+
+/// Verify a Groth16 proof over BN254 with the `alt_bn128` syscalls:
+/// `e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1`
+/// where `vk_x = ic[0] + sum(public_inputs[i] * ic[i + 1])`.
+#[cfg(feature = "zk")]
+#[allow(clippy::too_many_arguments)]
+pub fn verify_groth16(
+    vk_alpha_g1: &[u8; 64],
+    vk_beta_g2: &[u8; 128],
+    vk_gamma_g2: &[u8; 128],
+    vk_delta_g2: &[u8; 128],
+    vk_ic: &[[u8; 64]; ZK_PUBLIC_INPUTS + 1],
+    proof_a: &[u8; 64],
+    proof_b: &[u8; 128],
+    proof_c: &[u8; 64],
+    public_inputs: &[[u8; 32]; ZK_PUBLIC_INPUTS],
+) -> bool {
+    // a non-canonical input would let the same nullifier verify twice
+    if public_inputs
+        .iter()
+        .any(|input| *input >= BN254_SCALAR_MODULUS)
+    {
+        return false;
+    }
+
+    let mut vk_x = vk_ic[0];
+    for (input, ic) in public_inputs.iter().zip(&vk_ic[1..]) {
+        let Some(term) = alt_bn128_mul(ic, input) else {
+            return false;
+        };
+        let Some(sum) = alt_bn128_add(&vk_x, &term) else {
+            return false;
+        };
+        vk_x = sum;
+    }
+
+    let mut pairing_input = [0u8; 4 * 192];
+    let pairs: [(&[u8; 64], &[u8; 128]); 4] = [
+        (&alt_bn128_negate(proof_a), proof_b),
+        (vk_alpha_g1, vk_beta_g2),
+        (&vk_x, vk_gamma_g2),
+        (proof_c, vk_delta_g2),
+    ];
+    for (chunk, (g1, g2)) in pairing_input.chunks_exact_mut(192).zip(pairs) {
+        chunk[..64].copy_from_slice(g1);
+        chunk[64..].copy_from_slice(g2);
+    }
+
+    match alt_bn128_group_op::<32>(ALT_BN128_PAIRING, &pairing_input) {
+        Some(result) => result[31] == 1 && result[..31] == [0u8; 31],
+        None => false,
+    }
 }
@@ -0,0 +1,180 @@
+//! Account checks shared by every instruction. Each kind of failure maps to
+//! its own error code, so a client can tell a missing signature from a wrong
+//! owner, a wrong address or a wrong program.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{self, Pubkey},
+    sysvars::instructions::{Instructions, INSTRUCTIONS_ID},
+};
+
+use crate::{
+    errors::AirdropProgramError,
+    states::{AirdropState, Config},
+    utils::AccountHeader,
+};
+
+/// Loader of upgradeable programs, owner of this program's `ProgramData`
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+    pinocchio_pubkey::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// `MissingRequiredSignature` unless `account` signed the transaction
+#[inline(always)]
+pub fn check_signer(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// `AccountNotWritable` unless `account` is writable
+#[inline(always)]
+pub fn check_writable(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_writable() {
+        return Err(AirdropProgramError::AccountNotWritable.into());
+    }
+    Ok(())
+}
+
+/// `InvalidAccountOwner` unless `account` is owned by this program
+#[inline(always)]
+pub fn check_program_owned(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.is_owned_by(&crate::ID) {
+        return Err(AirdropProgramError::InvalidAccountOwner.into());
+    }
+    Ok(())
+}
+
+/// `UninitializedAccount` if `account` holds no data
+#[inline(always)]
+pub fn check_initialized(account: &AccountInfo) -> Result<(), ProgramError> {
+    if account.data_is_empty() {
+        return Err(AirdropProgramError::UninitializedAccount.into());
+    }
+    Ok(())
+}
+
+/// `AccountAlreadyInitialized` unless `account` is still empty, so a PDA is
+/// never created twice
+#[inline(always)]
+pub fn check_uninitialized(account: &AccountInfo) -> Result<(), ProgramError> {
+    if !account.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    Ok(())
+}
+
+/// `InvalidSystemProgram` unless `account` is the system program
+#[inline(always)]
+pub fn check_system_program(account: &AccountInfo) -> Result<(), ProgramError> {
+    if account.key().ne(&pinocchio_system::ID) {
+        return Err(AirdropProgramError::InvalidSystemProgram.into());
+    }
+    Ok(())
+}
+
+/// `InvalidPda` unless `target` is the PDA `find_program_address` derives
+/// from `seeds` and `bump` is its canonical bump. Any other off-curve bump
+/// gives the same seeds another address, so a marker created there would not
+/// stop a second claim.
+pub fn check_canonical_pda(target: &Pubkey, seeds: &[&[u8]], bump: u8) -> Result<(), ProgramError> {
+    let (expected, canonical_bump) = pubkey::find_program_address(seeds, &crate::ID);
+    if expected != *target || bump != canonical_bump {
+        return Err(AirdropProgramError::InvalidPda.into());
+    }
+    Ok(())
+}
+
+/// Check that `account` is the initialized campaign state: owned by this
+/// program, with the state layout, at the canonical PDA its stored bump names.
+pub fn check_airdrop_state(account: &AccountInfo, writable: bool) -> Result<(), ProgramError> {
+    check_program_owned(account)?;
+    check_initialized(account)?;
+    if writable {
+        check_writable(account)?;
+    }
+
+    let airdrop_state = AirdropState::load(account)?;
+    AirdropState::validate_pda(account.key(), airdrop_state.bump[0])
+}
+
+/// Check that `account` can hold the funds of a campaign. Whether it is the
+/// vault of that campaign is checked against the state with
+/// `AirdropState::validate_vault`.
+#[inline(always)]
+pub fn check_vault(account: &AccountInfo) -> Result<(), ProgramError> {
+    check_program_owned(account)?;
+    check_writable(account)
+}
+
+/// `InvalidArgument` unless `account` is the instructions sysvar
+#[inline(always)]
+pub fn check_instructions_sysvar(account: &AccountInfo) -> Result<(), ProgramError> {
+    if account.key().ne(&INSTRUCTIONS_ID) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
+
+/// Check a claim against the campaign's CPI policy. Only top-level
+/// instructions are visible in the instructions sysvar, so the caller checked
+/// is the program at the top of the call chain. The sysvar is only needed
+/// when the policy restricts callers.
+pub fn check_cpi_policy(
+    airdrop_state: &AirdropState,
+    instructions_sysvar: Option<&AccountInfo>,
+) -> Result<(), ProgramError> {
+    if airdrop_state.cpi_policy[0] == AirdropState::CPI_ALLOW_ALL {
+        return Ok(());
+    }
+
+    let instructions_sysvar = instructions_sysvar.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let instructions = Instructions::try_from(instructions_sysvar)?;
+    let current = instructions.load_instruction_at(instructions.load_current_index() as usize)?;
+
+    if !airdrop_state.allows_caller(current.get_program_id()) {
+        return Err(AirdropProgramError::CpiNotAllowed.into());
+    }
+    Ok(())
+}
+
+/// Check that `account` is the config PDA: either not created yet or holding
+/// the config layout.
+pub fn check_config(account: &AccountInfo) -> Result<(), ProgramError> {
+    if account.key().ne(&Config::ADDRESS) {
+        return Err(AirdropProgramError::InvalidPda.into());
+    }
+    if account.data_is_empty() {
+        return Ok(());
+    }
+    check_program_owned(account)?;
+    Config::load(account).map(|_| ())
+}
+
+/// `Unauthorized` unless `authority` is the upgrade authority stored in
+/// `program_data`, the `ProgramData` account of this program.
+pub fn check_upgrade_authority(
+    program_data: &AccountInfo,
+    authority: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let (expected, _) = pubkey::find_program_address(&[&crate::ID], &BPF_LOADER_UPGRADEABLE_ID);
+    if program_data.key().ne(&expected) {
+        return Err(AirdropProgramError::InvalidPda.into());
+    }
+    if !program_data.is_owned_by(&BPF_LOADER_UPGRADEABLE_ID) {
+        return Err(AirdropProgramError::InvalidAccountOwner.into());
+    }
+
+    // `ProgramData` variant (u32 3), deploy slot (u64), then the authority
+    // as an `Option<Pubkey>`
+    let data = program_data.try_borrow_data()?;
+    let is_authority = data.len() >= 45
+        && data[..4] == 3u32.to_le_bytes()
+        && data[12] == 1
+        && data[13..45] == *authority.key();
+    if !is_authority {
+        return Err(AirdropProgramError::Unauthorized.into());
+    }
+    Ok(())
+}
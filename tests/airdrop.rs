@@ -1,3 +1,6 @@
+// the merkle helpers below and every campaign in these tests use keccak
+#![cfg(feature = "keccak")]
+
 #[cfg(test)]
 mod tests_airdrop_distributor {
     use mollusk_svm::{
@@ -7,11 +10,12 @@ mod tests_airdrop_distributor {
 
     use pinocchio_airdrop_distributor::{
         instructions::{
-            ClaimAirdropInstructionData, InitializeAirdropInstructionData,
-            UpdateMerkleRootInstructionData,
+            ClaimAirdropInstructionData, ClaimBatchInstructionData, ClaimRecord,
+            InitializeAirdropInstructionData, UpdateMerkleRootInstructionData,
+            WriteProofBufferInstructionData,
         },
-        states::{AirdropState, ClaimStatus},
-        utils::{to_bytes, DataLen},
+        states::{AirdropState, ClaimStatus, Config, ProofBuffer, Redirect, Vault},
+        utils::{to_bytes, AccountHeader, DataLen, HashFunction},
         *,
     };
     use solana_sdk::{
@@ -88,7 +92,7 @@ mod tests_airdrop_distributor {
                 if chunk.len() == 2 {
                     // Nếu current_index nằm trong chunk này
                     if current_index / 2 == i {
-                        if current_index % 2 == 0 {
+                        if current_index.is_multiple_of(2) {
                             // Current node là left child, add right sibling
                             proof.push(chunk[1]);
                         } else {
@@ -100,7 +104,10 @@ mod tests_airdrop_distributor {
                     let parent = hash_pair(&chunk[0], &chunk[1]);
                     next_level.push(parent);
                 } else {
-                    // Odd number case
+                    // Odd number case, the last node is paired with itself
+                    if current_index / 2 == i {
+                        proof.push(chunk[0]);
+                    }
                     let parent = hash_pair(&chunk[0], &chunk[0]);
                     next_level.push(parent);
                 }
@@ -113,9 +120,78 @@ mod tests_airdrop_distributor {
         proof
     }
 
+    fn create_merkle_multiproof(
+        airdrop_data: &[(Pubkey, u64)],
+        target_indices: &[usize],
+    ) -> (Vec<[u8; 32]>, Vec<bool>) {
+        use pinocchio_airdrop_distributor::utils::{create_airdrop_leaf, hash_pair};
+
+        let mut level: Vec<[u8; 32]> = airdrop_data
+            .iter()
+            .map(|(pubkey, amount)| create_airdrop_leaf(&pubkey.to_bytes(), *amount, 0))
+            .collect();
+
+        let mut known: Vec<usize> = target_indices.to_vec();
+        let mut proof = Vec::new();
+        let mut proof_flags = Vec::new();
+
+        // Walk up level by level, in the same order the program consumes nodes
+        while level.len() > 1 {
+            let mut next_known = Vec::new();
+            let mut i = 0;
+            while i < known.len() {
+                let index = known[i];
+                let sibling = index ^ 1;
+                if i + 1 < known.len() && known[i + 1] == sibling {
+                    // both children are known, hash them together
+                    proof_flags.push(true);
+                    i += 2;
+                } else {
+                    // sibling comes from the proof; the odd last node pairs with itself
+                    proof.push(*level.get(sibling).unwrap_or(&level[index]));
+                    proof_flags.push(false);
+                    i += 1;
+                }
+                next_known.push(index / 2);
+            }
+
+            level = level
+                .chunks(2)
+                .map(|chunk| hash_pair(&chunk[0], chunk.get(1).unwrap_or(&chunk[0])))
+                .collect();
+            known = next_known;
+        }
+
+        (proof, proof_flags)
+    }
+
+    fn redirect_of(airdrop: &Pubkey, recipient: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[Redirect::SEED, airdrop.as_ref(), recipient.as_ref()],
+            &PROGRAM_ID,
+        )
+        .0
+    }
+
+    /// An off-curve PDA of `seeds` at a bump below the canonical one, the
+    /// second address a caller could try for the same seeds
+    fn non_canonical_pda(seeds: &[&[u8]]) -> (Pubkey, u8) {
+        let (_, canonical_bump) = Pubkey::find_program_address(seeds, &PROGRAM_ID);
+        (0..canonical_bump)
+            .rev()
+            .find_map(|bump| {
+                let bump_binding = [bump];
+                let mut seeds_with_bump = seeds.to_vec();
+                seeds_with_bump.push(&bump_binding);
+                Pubkey::create_program_address(&seeds_with_bump, &PROGRAM_ID)
+                    .ok()
+                    .map(|address| (address, bump))
+            })
+            .unwrap()
+    }
+
     fn get_mollusk() -> Mollusk {
-        let mollusk = Mollusk::new(&PROGRAM_ID, "target/deploy/pinocchio_airdrop_distributor");
-        mollusk
+        Mollusk::new(&PROGRAM_ID, "target/deploy/pinocchio_airdrop_distributor")
     }
 
     #[test]
@@ -161,7 +237,7 @@ mod tests_airdrop_distributor {
         let root = hash_pair(&leaf1, &leaf2);
 
         let proof = vec![leaf2];
-        let is_valid = verify_merkle_proof(&leaf1, &proof, 0, &root);
+        let is_valid = verify_merkle_proof(HashFunction::Keccak256, &leaf1, &proof, 0, &root);
         assert!(is_valid);
 
         println!("✅ All hash functions work correctly");
@@ -175,12 +251,15 @@ mod tests_airdrop_distributor {
             mollusk_svm::program::keyed_account_for_system_program();
 
         let maker = Pubkey::new_from_array([0x02; 32]);
-        let maker_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let (airdrop_address, bump) =
             Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
 
         let airdrop_account = Account::new(0, 0, &system_program);
+        let vault_account = Account::new(0, 0, &system_program);
 
         let airdrop_recipients = vec![
             (Pubkey::new_unique(), 100_000_000u64),
@@ -195,9 +274,15 @@ mod tests_airdrop_distributor {
             merkle_root,
             amount,
             bump,
+            operator: [0u8; 32],
+            distribution_mode: AirdropState::MODE_SELF_CLAIM,
+            hash_fn: HashFunction::Keccak256 as u8,
+            claim_key: AirdropState::CLAIM_KEY_CLAIMER,
+            vault_bump,
         };
 
         let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
 
         let mut data = vec![0];
         data.extend_from_slice(unsafe { to_bytes(&ix_data) });
@@ -207,6 +292,7 @@ mod tests_airdrop_distributor {
             &data,
             vec![
                 AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
                 AccountMeta::new(maker, true),
                 AccountMeta::new_readonly(system_program, false),
             ],
@@ -217,6 +303,7 @@ mod tests_airdrop_distributor {
                 &instruction,
                 &[
                     (airdrop_address, airdrop_account.clone()),
+                    (vault_address, vault_account.clone()),
                     (maker, maker_account.clone()),
                     (system_program, system_account.clone()),
                 ],
@@ -224,16 +311,169 @@ mod tests_airdrop_distributor {
                     Check::success(),
                     Check::account(&airdrop_address).owner(&PROGRAM_ID).build(),
                     Check::account(&airdrop_address)
-                        .lamports(amount + lamport_for_rent)
+                        .lamports(lamport_for_rent)
+                        .build(),
+                    Check::account(&vault_address).owner(&PROGRAM_ID).build(),
+                    Check::account(&vault_address)
+                        .lamports(amount + vault_rent)
                         .build(),
                 ],
             );
 
-        // check balance of aidrop_state
+        // check balance of the vault
 
-        let airdrop_account = result.get_account(&airdrop_address).unwrap();
-        print!("{}", airdrop_account.lamports());
-        assert!(airdrop_account.lamports() >= amount);
+        let vault_account = result.get_account(&vault_address).unwrap();
+        print!("{}", vault_account.lamports());
+        assert!(vault_account.lamports() >= amount);
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn init_airdrop_state_failure_when_bump_is_not_canonical() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        // a second off-curve address of the campaign seed
+        let (airdrop_address, bump) = non_canonical_pda(&[AirdropState::SEED]);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account = Account::new(0, 0, &system_program);
+        let vault_account = Account::new(0, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+
+        let amount = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let ix_data = InitializeAirdropInstructionData {
+            merkle_root,
+            amount,
+            bump,
+            operator: [0u8; 32],
+            distribution_mode: AirdropState::MODE_SELF_CLAIM,
+            hash_fn: HashFunction::Keccak256 as u8,
+            claim_key: AirdropState::CLAIM_KEY_CLAIMER,
+            vault_bump,
+        };
+
+        let mut data = vec![0];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.clone()),
+                    (vault_address, vault_account.clone()),
+                    (maker, maker_account.clone()),
+                    (system_program, system_account.clone()),
+                ],
+                &[Check::err(ProgramError::Custom(11))], // invalid_pda
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn init_airdrop_state_with_prefunded_vault() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let (airdrop_address, bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account = Account::new(0, 0, &system_program);
+        // someone sent lamports to the vault PDA before the campaign existed
+        let vault_account = Account::new(1, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+
+        let amount = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let ix_data = InitializeAirdropInstructionData {
+            merkle_root,
+            amount,
+            bump,
+            operator: [0u8; 32],
+            distribution_mode: AirdropState::MODE_SELF_CLAIM,
+            hash_fn: HashFunction::Keccak256 as u8,
+            claim_key: AirdropState::CLAIM_KEY_CLAIMER,
+            vault_bump,
+        };
+
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+
+        let mut data = vec![0];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&airdrop_address).owner(&PROGRAM_ID).build(),
+                    Check::account(&airdrop_address)
+                        .lamports(lamport_for_rent)
+                        .build(),
+                    Check::account(&vault_address).owner(&PROGRAM_ID).build(),
+                    Check::account(&vault_address)
+                        .lamports(amount + vault_rent)
+                        .build(),
+                    Check::account(&maker)
+                        .lamports(LAMPORTS_PER_SOL - lamport_for_rent - amount - vault_rent + 1)
+                        .build(),
+                ],
+            );
         assert!(result.program_result == ProgramResult::Success);
     }
 
@@ -245,10 +485,10 @@ mod tests_airdrop_distributor {
             mollusk_svm::program::keyed_account_for_system_program();
 
         let maker = Pubkey::new_from_array([0x02; 32]);
-        let _maker_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+        let _maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let claimer = Pubkey::new_from_array([0x03; 32]);
-        let claimer_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let airdrop_recipients = vec![
             (Pubkey::new_unique(), 100_000_000u64),
@@ -263,24 +503,40 @@ mod tests_airdrop_distributor {
 
         let (airdrop_address, airdrop_account_bump) =
             Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
 
         let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
             authority: maker.to_bytes(),
             merkle_root,
-            airdrop_amount: amount.to_le_bytes(),
-            amount_claimed: 0u64.to_le_bytes(),
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
             bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
         };
         let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
 
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
         let mut airdrop_account =
-            AccountSharedData::new(lamport_for_rent + amount, AirdropState::LEN, &PROGRAM_ID);
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
 
         airdrop_account
             .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
 
         let leaf_index = 3;
-        let proof = create_merkle_proof(&airdrop_recipients, leaf_index as usize);
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
 
         let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
             &[
@@ -308,14 +564,24 @@ mod tests_airdrop_distributor {
             data.extend_from_slice(proof_element);
         }
 
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
         let instruction = Instruction::new_with_bytes(
             PROGRAM_ID,
             &data,
             vec![
                 AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
                 AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
                 AccountMeta::new(user_claim_address, false),
                 AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
             ],
         );
 
@@ -324,9 +590,12 @@ mod tests_airdrop_distributor {
                 &instruction,
                 &[
                     (airdrop_address, airdrop_account.into()),
-                    (claimer, claimer_account.into()),
-                    (user_claim_address, user_claim_account.into()),
-                    (system_program, system_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
                 ],
                 &[
                     Check::success(),
@@ -334,8 +603,8 @@ mod tests_airdrop_distributor {
                     Check::account(&user_claim_address)
                         .owner(&PROGRAM_ID)
                         .build(),
-                    Check::account(&airdrop_address)
-                        .lamports(amount + lamport_for_rent - airdrop_recipients[leaf_index].1)
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount - airdrop_recipients[leaf_index].1)
                         .build(),
                     // Check::account(&claimer)
                     //     .lamports(1 * LAMPORTS_PER_SOL + airdrop_recipients[leaf_index].1)
@@ -345,6 +614,132 @@ mod tests_airdrop_distributor {
         assert!(result.program_result == ProgramResult::Success);
     }
 
+    #[test]
+    fn claim_airdrop_failure_when_vault_underfunded() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let _maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (claimer, 50_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+            (Pubkey::new_unique(), 125_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        // paying the leaf would eat into the vault's rent
+        let vault_account = Account::new(vault_rent + 1, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 3;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+                &[Check::err(ProgramError::Custom(19))], // insufficient_funds
+            );
+        assert!(result.program_result.is_err());
+    }
+
     #[test]
     fn claim_airdrop_failt_with_invalid_proof() {
         let mollusk = get_mollusk();
@@ -353,10 +748,10 @@ mod tests_airdrop_distributor {
             mollusk_svm::program::keyed_account_for_system_program();
 
         let maker = Pubkey::new_from_array([0x02; 32]);
-        let _maker_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+        let _maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let claimer = Pubkey::new_from_array([0x04; 32]);
-        let claimer_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let airdrop_recipients = vec![
             (Pubkey::new_unique(), 100_000_000u64),
@@ -371,24 +766,40 @@ mod tests_airdrop_distributor {
 
         let (airdrop_address, airdrop_account_bump) =
             Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
 
         let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
             authority: maker.to_bytes(),
             merkle_root,
-            airdrop_amount: amount.to_le_bytes(),
-            amount_claimed: 0u64.to_le_bytes(),
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
             bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
         };
         let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
 
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
         let mut airdrop_account =
-            AccountSharedData::new(lamport_for_rent + amount, AirdropState::LEN, &PROGRAM_ID);
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
 
         airdrop_account
             .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
 
         let leaf_index = 3;
-        let proof = create_merkle_proof(&airdrop_recipients, leaf_index as usize);
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
 
         let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
             &[
@@ -415,14 +826,24 @@ mod tests_airdrop_distributor {
             data.extend_from_slice(proof_element);
         }
 
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
         let instruction = Instruction::new_with_bytes(
             PROGRAM_ID,
             &data,
             vec![
                 AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
                 AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
                 AccountMeta::new(user_claim_address, false),
                 AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
             ],
         );
 
@@ -431,15 +852,18 @@ mod tests_airdrop_distributor {
                 &instruction,
                 &[
                     (airdrop_address, airdrop_account.into()),
-                    (claimer, claimer_account.into()),
-                    (user_claim_address, user_claim_account.into()),
-                    (system_program, system_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
                 ],
                 &[
                     Check::err(ProgramError::Custom(0)), // invalid_proof
                     Check::account(&airdrop_address).owner(&PROGRAM_ID).build(),
-                    Check::account(&airdrop_address)
-                        .lamports(amount + lamport_for_rent)
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount)
                         .build(),
                 ],
             );
@@ -454,10 +878,10 @@ mod tests_airdrop_distributor {
             mollusk_svm::program::keyed_account_for_system_program();
 
         let maker = Pubkey::new_from_array([0x02; 32]);
-        let _maker_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+        let _maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let claimer = Pubkey::new_from_array([0x03; 32]);
-        let claimer_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let airdrop_recipients = vec![
             (Pubkey::new_unique(), 100_000_000u64),
@@ -472,24 +896,40 @@ mod tests_airdrop_distributor {
 
         let (airdrop_address, airdrop_account_bump) =
             Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
 
         let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
             authority: maker.to_bytes(),
             merkle_root,
-            airdrop_amount: amount.to_le_bytes(),
-            amount_claimed: 0u64.to_le_bytes(),
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
             bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
         };
         let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
 
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
         let mut airdrop_account =
-            AccountSharedData::new(lamport_for_rent + amount, AirdropState::LEN, &PROGRAM_ID);
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
 
         airdrop_account
             .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
 
         let leaf_index = 3;
-        let proof = create_merkle_proof(&airdrop_recipients, leaf_index as usize);
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
 
         let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
             &[
@@ -501,7 +941,11 @@ mod tests_airdrop_distributor {
         );
 
         let user_claim_data = ClaimStatus {
+            discriminator: ClaimStatus::DISCRIMINATOR,
+            version: [ClaimStatus::VERSION],
             bump: [user_claim_account_bump],
+            revoked: [0],
+            payer: claimer.to_bytes(),
         };
 
         let mut user_claim_account = AccountSharedData::new(0, ClaimStatus::LEN, &system_program);
@@ -521,14 +965,24 @@ mod tests_airdrop_distributor {
             data.extend_from_slice(proof_element);
         }
 
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
         let instruction = Instruction::new_with_bytes(
             PROGRAM_ID,
             &data,
             vec![
                 AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
                 AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
                 AccountMeta::new(user_claim_address, false),
                 AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
             ],
         );
 
@@ -537,15 +991,18 @@ mod tests_airdrop_distributor {
                 &instruction,
                 &[
                     (airdrop_address, airdrop_account.into()),
-                    (claimer, claimer_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
                     (user_claim_address, user_claim_account.into()),
-                    (system_program, system_account.into()),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
                 ],
                 &[
                     Check::err(ProgramError::Custom(2)), // already_claimed
                     Check::account(&airdrop_address).owner(&PROGRAM_ID).build(),
-                    Check::account(&airdrop_address)
-                        .lamports(amount + lamport_for_rent)
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount)
                         .build(),
                 ],
             );
@@ -560,7 +1017,7 @@ mod tests_airdrop_distributor {
             mollusk_svm::program::keyed_account_for_system_program();
 
         let maker = Pubkey::new_from_array([0x02; 32]);
-        let maker_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let old_airdrop_recipients = vec![
             (Pubkey::new_unique(), 100_000_000u64),
@@ -574,21 +1031,34 @@ mod tests_airdrop_distributor {
 
         let (airdrop_address, airdrop_account_bump) =
             Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
 
         let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
             authority: maker.to_bytes(),
             merkle_root: old_merkle_root,
-            airdrop_amount: old_amount.to_le_bytes(),
-            amount_claimed: 0u64.to_le_bytes(),
+            airdrop_amount: old_amount.into(),
+            amount_claimed: 0u64.into(),
             bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
         };
         let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
 
-        let mut airdrop_account = AccountSharedData::new(
-            lamport_for_rent + old_amount,
-            AirdropState::LEN,
-            &PROGRAM_ID,
-        );
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + old_amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
 
         airdrop_account
             .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
@@ -616,6 +1086,7 @@ mod tests_airdrop_distributor {
             &data,
             vec![
                 AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
                 AccountMeta::new(maker, true),
                 AccountMeta::new_readonly(system_program, false),
             ],
@@ -626,8 +1097,9 @@ mod tests_airdrop_distributor {
                 &instruction,
                 &[
                     (airdrop_address, airdrop_account.into()),
-                    (maker, maker_account.into()),
-                    (system_program, system_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                    (system_program, system_account),
                 ],
                 &[
                     Check::success(),
@@ -638,14 +1110,14 @@ mod tests_airdrop_distributor {
     }
 
     #[test]
-    fn update_merkle_tree_failure_with_unauthorized() {
+    fn update_merkle_tree_failure_without_authority_signature() {
         let mollusk = get_mollusk();
 
         let (system_program, system_account) =
             mollusk_svm::program::keyed_account_for_system_program();
 
         let maker = Pubkey::new_from_array([0x02; 32]);
-        let _maker_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let old_airdrop_recipients = vec![
             (Pubkey::new_unique(), 100_000_000u64),
@@ -659,21 +1131,34 @@ mod tests_airdrop_distributor {
 
         let (airdrop_address, airdrop_account_bump) =
             Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
 
         let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
             authority: maker.to_bytes(),
             merkle_root: old_merkle_root,
-            airdrop_amount: old_amount.to_le_bytes(),
-            amount_claimed: 0u64.to_le_bytes(),
+            airdrop_amount: old_amount.into(),
+            amount_claimed: 0u64.into(),
             bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
         };
         let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
 
-        let mut airdrop_account = AccountSharedData::new(
-            lamport_for_rent + old_amount,
-            AirdropState::LEN,
-            &PROGRAM_ID,
-        );
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + old_amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
 
         airdrop_account
             .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
@@ -696,15 +1181,14 @@ mod tests_airdrop_distributor {
         let mut data = vec![2];
         data.extend_from_slice(unsafe { to_bytes(&ix_data) });
 
-        let fake_maker = Pubkey::new_from_array([0x05; 32]);
-        let fake_maker_account = Account::new(1 * LAMPORTS_PER_SOL, 0, &system_program);
-
         let instruction = Instruction::new_with_bytes(
             PROGRAM_ID,
             &data,
             vec![
                 AccountMeta::new(airdrop_address, false),
-                AccountMeta::new(fake_maker, true),
+                AccountMeta::new(vault_address, false),
+                // the authority key matches, but it did not sign
+                AccountMeta::new(maker, false),
                 AccountMeta::new_readonly(system_program, false),
             ],
         );
@@ -714,14 +1198,218 @@ mod tests_airdrop_distributor {
                 &instruction,
                 &[
                     (airdrop_address, airdrop_account.into()),
-                    (fake_maker, fake_maker_account.into()),
-                    (system_program, system_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                    (system_program, system_account),
                 ],
-                &[
-                    Check::err(ProgramError::Custom(1)), // unauthorized
-                    Check::account(&airdrop_address).owner(&PROGRAM_ID).build(),
-                    Check::account(&airdrop_address)
-                        .data(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) })
+                &[Check::err(ProgramError::MissingRequiredSignature)],
+            );
+        assert!(
+            result.program_result == ProgramResult::Failure(ProgramError::MissingRequiredSignature)
+        );
+    }
+
+    #[test]
+    fn update_merkle_tree_failure_with_fake_system_program() {
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let old_airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+            (Pubkey::new_unique(), 125_000_000u64),
+        ];
+        let old_merkle_root = create_merkle_root(&old_airdrop_recipients);
+        let old_amount: u64 = old_airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: old_merkle_root,
+            airdrop_amount: old_amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + old_amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let new_airdrop_recipients = vec![
+            (Pubkey::new_unique(), 300_000_000u64),
+            (Pubkey::new_unique(), 20_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (Pubkey::new_unique(), 720_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+        ];
+        let new_merkle_root = create_merkle_root(&new_airdrop_recipients);
+        let new_amount: u64 = new_airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let ix_data = UpdateMerkleRootInstructionData {
+            new_merkle_root,
+            additional_amount: new_amount - old_amount,
+        };
+
+        let mut data = vec![2];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // an account that merely sits where the system program should be
+        let fake_system_program = Pubkey::new_unique();
+        let fake_system_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new_readonly(fake_system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                    (fake_system_program, fake_system_account),
+                ],
+                &[Check::err(ProgramError::Custom(12))], // invalid_system_program
+            );
+        assert!(result.program_result == ProgramResult::Failure(ProgramError::Custom(12)));
+    }
+
+    #[test]
+    fn update_merkle_tree_failure_with_unauthorized() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let _maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let old_airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+            (Pubkey::new_unique(), 125_000_000u64),
+        ];
+        let old_merkle_root = create_merkle_root(&old_airdrop_recipients);
+        let old_amount: u64 = old_airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: old_merkle_root,
+            airdrop_amount: old_amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + old_amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let new_airdrop_recipients = vec![
+            (Pubkey::new_unique(), 300_000_000u64),
+            (Pubkey::new_unique(), 20_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (Pubkey::new_unique(), 720_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+        ];
+        let new_merkle_root = create_merkle_root(&new_airdrop_recipients);
+        let new_amount: u64 = new_airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let ix_data = UpdateMerkleRootInstructionData {
+            new_merkle_root,
+            additional_amount: new_amount - old_amount,
+        };
+
+        let mut data = vec![2];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let fake_maker = Pubkey::new_from_array([0x05; 32]);
+        let fake_maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(fake_maker, true),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (fake_maker, fake_maker_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::err(ProgramError::Custom(1)), // unauthorized
+                    Check::account(&airdrop_address).owner(&PROGRAM_ID).build(),
+                    Check::account(&airdrop_address)
+                        .data(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) })
                         .build(),
                 ],
             );
@@ -729,41 +1417,6952 @@ mod tests_airdrop_distributor {
     }
 
     #[test]
-    fn test_create_merkle_root_and_proof() {
-        use pinocchio_airdrop_distributor::utils::{create_airdrop_leaf, verify_merkle_proof};
+    fn close_airdrop_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
 
         let airdrop_recipients = vec![
-            (Pubkey::new_unique(), 1000u64),
-            (Pubkey::new_unique(), 2000u64),
-            (Pubkey::new_unique(), 1500u64),
-            (Pubkey::new_unique(), 3000u64),
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
         ];
-
         let merkle_root = create_merkle_root(&airdrop_recipients);
-        println!(
-            "Generated merkle root (first 8 bytes): {:?}",
-            &merkle_root[..8]
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &[3],
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+            ],
         );
 
-        let empty_root = create_merkle_root(&[]);
-        assert_eq!(empty_root, [0u8; 32]);
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&airdrop_address)
+                        .lamports(lamport_for_rent)
+                        .build(),
+                    Check::account(&vault_address).lamports(vault_rent).build(),
+                    Check::account(&maker)
+                        .lamports(LAMPORTS_PER_SOL + amount)
+                        .build(),
+                ],
+            );
 
-        let single_recipient = vec![(Pubkey::new_unique(), 5000u64)];
-        let single_root = create_merkle_root(&single_recipient);
+        let airdrop_account = result.get_account(&airdrop_address).unwrap();
+        let closed_offset = core::mem::offset_of!(AirdropState, closed);
+        assert_eq!(airdrop_account.data()[closed_offset], 1);
+    }
 
-        let expected_leaf =
-            create_airdrop_leaf(&single_recipient[0].0.to_bytes(), single_recipient[0].1, 0);
-        assert_eq!(single_root, expected_leaf);
+    #[test]
+    fn close_claim_status_success() {
+        let mollusk = get_mollusk();
 
-        for (index, (pubkey, amount)) in airdrop_recipients.iter().enumerate() {
-            let proof = create_merkle_proof(&airdrop_recipients, index);
-            let leaf = create_airdrop_leaf(&pubkey.to_bytes(), *amount, 0);
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
 
-            // Verify proof
-            let is_valid = verify_merkle_proof(&leaf, &proof, index as u64, &merkle_root);
-            assert!(is_valid, "Proof verification failed for index {}", index);
-        }
+        let maker = Pubkey::new_from_array([0x02; 32]);
 
-        println!("✅ Merkle root creation and proof verification successful");
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [1],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_data = ClaimStatus {
+            discriminator: ClaimStatus::DISCRIMINATOR,
+            version: [ClaimStatus::VERSION],
+            bump: [user_claim_account_bump],
+            revoked: [0],
+            payer: claimer.to_bytes(),
+        };
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(ClaimStatus::LEN);
+
+        let mut user_claim_account =
+            AccountSharedData::new(claim_rent, ClaimStatus::LEN, &PROGRAM_ID);
+        user_claim_account.set_data_from_slice(unsafe { to_bytes(&user_claim_data) });
+
+        let mut data = vec![4];
+        data.extend_from_slice(claimer.as_ref());
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(user_claim_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account.into()),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address).closed().build(),
+                    Check::account(&claimer)
+                        .lamports(LAMPORTS_PER_SOL + claim_rent)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn close_claim_status_refunds_payer_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+
+        // an operator submitted the claim and paid the status rent
+        let operator = Pubkey::new_from_array([0x05; 32]);
+        let operator_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [1],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_data = ClaimStatus {
+            discriminator: ClaimStatus::DISCRIMINATOR,
+            version: [ClaimStatus::VERSION],
+            bump: [user_claim_account_bump],
+            revoked: [0],
+            payer: operator.to_bytes(),
+        };
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(ClaimStatus::LEN);
+
+        let mut user_claim_account =
+            AccountSharedData::new(claim_rent, ClaimStatus::LEN, &PROGRAM_ID);
+        user_claim_account.set_data_from_slice(unsafe { to_bytes(&user_claim_data) });
+
+        let mut data = vec![4];
+        data.extend_from_slice(claimer.as_ref());
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(operator, true),
+                AccountMeta::new(user_claim_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (operator, operator_account),
+                    (user_claim_address, user_claim_account.into()),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address).closed().build(),
+                    Check::account(&operator)
+                        .lamports(LAMPORTS_PER_SOL + claim_rent)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn close_claim_status_failure_when_signer_did_not_pay() {
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        // an operator submitted the claim and paid the status rent
+        let operator = Pubkey::new_from_array([0x05; 32]);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [1],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_data = ClaimStatus {
+            discriminator: ClaimStatus::DISCRIMINATOR,
+            version: [ClaimStatus::VERSION],
+            bump: [user_claim_account_bump],
+            revoked: [0],
+            payer: operator.to_bytes(),
+        };
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(ClaimStatus::LEN);
+
+        let mut user_claim_account =
+            AccountSharedData::new(claim_rent, ClaimStatus::LEN, &PROGRAM_ID);
+        user_claim_account.set_data_from_slice(unsafe { to_bytes(&user_claim_data) });
+
+        let mut data = vec![4];
+        data.extend_from_slice(claimer.as_ref());
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(user_claim_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account.into()),
+                ],
+                &[Check::err(ProgramError::Custom(1))],
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn close_legacy_claim_status_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [1],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        // written by the first deployment: the bump and nothing else
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(1);
+
+        let mut user_claim_account = AccountSharedData::new(claim_rent, 1, &PROGRAM_ID);
+        user_claim_account.set_data_from_slice(&[user_claim_account_bump]);
+
+        let mut data = vec![4];
+        data.extend_from_slice(claimer.as_ref());
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(user_claim_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account.into()),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address).closed().build(),
+                    Check::account(&claimer)
+                        .lamports(LAMPORTS_PER_SOL + claim_rent)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn close_evm_claim_status_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        // the holder has no Solana key; the relayer that submitted the claim
+        // paid for the status and reclaims its rent
+        let relayer = Pubkey::new_from_array([0x05; 32]);
+        let relayer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let evm_address = [0xAB; 20];
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [1],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::EVM_SEED,
+                airdrop_address.as_ref(),
+                evm_address.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_data = ClaimStatus {
+            discriminator: ClaimStatus::DISCRIMINATOR,
+            version: [ClaimStatus::VERSION],
+            bump: [user_claim_account_bump],
+            revoked: [0],
+            payer: relayer.to_bytes(),
+        };
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(ClaimStatus::LEN);
+
+        let mut user_claim_account =
+            AccountSharedData::new(claim_rent, ClaimStatus::LEN, &PROGRAM_ID);
+        user_claim_account.set_data_from_slice(unsafe { to_bytes(&user_claim_data) });
+
+        let mut data = vec![4];
+        data.extend_from_slice(&evm_address);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(relayer, true),
+                AccountMeta::new(user_claim_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (relayer, relayer_account),
+                    (user_claim_address, user_claim_account.into()),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address).closed().build(),
+                    Check::account(&relayer)
+                        .lamports(LAMPORTS_PER_SOL + claim_rent)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[cfg(feature = "zk")]
+    #[test]
+    fn close_nullifier_success() {
+        use pinocchio_airdrop_distributor::states::Nullifier;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        // nullifiers do not record their payer, so the authority reclaims the rent
+        let nullifier_hash = [0xCD; 32];
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [1],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                Nullifier::SEED,
+                airdrop_address.as_ref(),
+                nullifier_hash.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_data = Nullifier {
+            discriminator: Nullifier::DISCRIMINATOR,
+            version: [Nullifier::VERSION],
+            bump: [user_claim_account_bump],
+        };
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(Nullifier::LEN);
+
+        let mut user_claim_account =
+            AccountSharedData::new(claim_rent, Nullifier::LEN, &PROGRAM_ID);
+        user_claim_account.set_data_from_slice(unsafe { to_bytes(&user_claim_data) });
+
+        let mut data = vec![4];
+        data.extend_from_slice(&nullifier_hash);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new(user_claim_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (maker, maker_account),
+                    (user_claim_address, user_claim_account.into()),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address).closed().build(),
+                    Check::account(&maker)
+                        .lamports(LAMPORTS_PER_SOL + claim_rent)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn close_claim_status_failure_when_airdrop_open() {
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_data = ClaimStatus {
+            discriminator: ClaimStatus::DISCRIMINATOR,
+            version: [ClaimStatus::VERSION],
+            bump: [user_claim_account_bump],
+            revoked: [0],
+            payer: claimer.to_bytes(),
+        };
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(ClaimStatus::LEN);
+
+        let mut user_claim_account =
+            AccountSharedData::new(claim_rent, ClaimStatus::LEN, &PROGRAM_ID);
+        user_claim_account.set_data_from_slice(unsafe { to_bytes(&user_claim_data) });
+
+        // the authority tries to reclaim rent while the campaign is still live
+        let mut data = vec![4];
+        data.extend_from_slice(claimer.as_ref());
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new(user_claim_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (maker, maker_account),
+                    (user_claim_address, user_claim_account.into()),
+                ],
+                &[
+                    Check::err(ProgramError::Custom(4)), // airdrop_not_closed
+                    Check::account(&user_claim_address)
+                        .lamports(claim_rent)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Failure(ProgramError::Custom(4)));
+    }
+
+    #[test]
+    fn claim_batch_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (claimer, 20_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (claimer, 30_000_000u64),
+            (Pubkey::new_unique(), 50_000_000u64),
+            (Pubkey::new_unique(), 125_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let leaf_indices = [1usize, 3];
+        let (proof, proof_flags) = create_merkle_multiproof(&airdrop_recipients, &leaf_indices);
+        let header = ClaimBatchInstructionData {
+            bump: user_claim_account_bump,
+            count: leaf_indices.len() as u8,
+            proof_len: proof.len() as u8,
+        };
+
+        let mut data = vec![5];
+        data.extend_from_slice(unsafe { to_bytes(&header) });
+
+        for leaf_index in leaf_indices {
+            let record = ClaimRecord {
+                amount: airdrop_recipients[leaf_index].1,
+                leaf_index: leaf_index as u64,
+            };
+            data.extend_from_slice(unsafe { to_bytes(&record) });
+        }
+        data.extend(proof_flags.iter().map(|flag| *flag as u8));
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let claimed: u64 = leaf_indices
+            .iter()
+            .map(|index| airdrop_recipients[*index].1)
+            .sum();
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .build(),
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount - claimed)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn claim_batch_failure_with_repeated_leaf() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (claimer, 20_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (Pubkey::new_unique(), 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        // the same leaf twice must not pay out twice
+        let leaf_indices = [1usize, 1];
+        let (proof, mut proof_flags) = create_merkle_multiproof(&airdrop_recipients, &[1]);
+        proof_flags.push(true);
+        let header = ClaimBatchInstructionData {
+            bump: user_claim_account_bump,
+            count: leaf_indices.len() as u8,
+            proof_len: proof.len() as u8,
+        };
+
+        let mut data = vec![5];
+        data.extend_from_slice(unsafe { to_bytes(&header) });
+
+        for leaf_index in leaf_indices {
+            let record = ClaimRecord {
+                amount: airdrop_recipients[leaf_index].1,
+                leaf_index: leaf_index as u64,
+            };
+            data.extend_from_slice(unsafe { to_bytes(&record) });
+        }
+        data.extend(proof_flags.iter().map(|flag| *flag as u8));
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+                &[
+                    Check::err(ProgramError::Custom(0)), // invalid_proof
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Failure(ProgramError::Custom(0)));
+    }
+
+    #[test]
+    fn write_proof_buffer_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (proof_buffer_address, proof_buffer_bump) = Pubkey::find_program_address(
+            &[
+                ProofBuffer::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        let proof_buffer_account = Account::new(0, 0, &system_program);
+
+        // first chunk of a 3-element proof
+        let ix_data = WriteProofBufferInstructionData {
+            bump: proof_buffer_bump,
+            proof_len: 3,
+            offset: 0,
+        };
+        let mut data = vec![6];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+        data.extend_from_slice(&[0xaa; 32]);
+        data.extend_from_slice(&[0xbb; 32]);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(proof_buffer_address, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let mut expected_data = ProofBuffer::DISCRIMINATOR.to_vec();
+        expected_data.extend_from_slice(&[ProofBuffer::VERSION, proof_buffer_bump, 3]);
+        expected_data.extend_from_slice(&[0xaa; 32]);
+        expected_data.extend_from_slice(&[0xbb; 32]);
+        expected_data.extend_from_slice(&[0u8; 32]);
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (proof_buffer_address, proof_buffer_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&proof_buffer_address)
+                        .owner(&PROGRAM_ID)
+                        .space(ProofBuffer::space(3))
+                        .data(&expected_data)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn write_proof_buffer_failure_when_bump_is_not_canonical() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        // a second buffer for the same claimer, at another bump
+        let (proof_buffer_address, proof_buffer_bump) = non_canonical_pda(&[
+            ProofBuffer::SEED,
+            airdrop_address.as_ref(),
+            claimer.as_ref(),
+        ]);
+        let proof_buffer_account = Account::new(0, 0, &system_program);
+
+        // first chunk of a 3-element proof
+        let ix_data = WriteProofBufferInstructionData {
+            bump: proof_buffer_bump,
+            proof_len: 3,
+            offset: 0,
+        };
+        let mut data = vec![6];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+        data.extend_from_slice(&[0xaa; 32]);
+        data.extend_from_slice(&[0xbb; 32]);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(proof_buffer_address, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (proof_buffer_address, proof_buffer_account),
+                    (system_program, system_account),
+                ],
+                &[Check::err(ProgramError::Custom(11))], // invalid_pda
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn close_proof_buffer_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [1],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (proof_buffer_address, proof_buffer_bump) = Pubkey::find_program_address(
+            &[
+                ProofBuffer::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        // a buffer left behind by a claim that never went through
+        let mut buffer_data = ProofBuffer::DISCRIMINATOR.to_vec();
+        buffer_data.extend_from_slice(&[ProofBuffer::VERSION, proof_buffer_bump, 1]);
+        buffer_data.extend_from_slice(&[0xaa; 32]);
+        let buffer_rent = mollusk.sysvars.rent.minimum_balance(ProofBuffer::space(1));
+        let mut proof_buffer_account =
+            AccountSharedData::new(buffer_rent, ProofBuffer::space(1), &PROGRAM_ID);
+        proof_buffer_account.set_data_from_slice(&buffer_data);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &[20],
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(proof_buffer_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (proof_buffer_address, proof_buffer_account.into()),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&proof_buffer_address).closed().build(),
+                    Check::account(&claimer)
+                        .lamports(LAMPORTS_PER_SOL + buffer_rent)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn close_proof_buffer_failure_when_signer_is_not_claimer() {
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (proof_buffer_address, proof_buffer_bump) = Pubkey::find_program_address(
+            &[
+                ProofBuffer::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        // a buffer left behind by a claim that never went through
+        let mut buffer_data = ProofBuffer::DISCRIMINATOR.to_vec();
+        buffer_data.extend_from_slice(&[ProofBuffer::VERSION, proof_buffer_bump, 1]);
+        buffer_data.extend_from_slice(&[0xaa; 32]);
+        let buffer_rent = mollusk.sysvars.rent.minimum_balance(ProofBuffer::space(1));
+        let mut proof_buffer_account =
+            AccountSharedData::new(buffer_rent, ProofBuffer::space(1), &PROGRAM_ID);
+        proof_buffer_account.set_data_from_slice(&buffer_data);
+
+        // someone else cannot take the claimer's buffer rent
+        let other = Pubkey::new_from_array([0x07; 32]);
+        let other_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &[20],
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(other, true),
+                AccountMeta::new(proof_buffer_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (other, other_account),
+                    (proof_buffer_address, proof_buffer_account.into()),
+                ],
+                &[Check::err(ProgramError::Custom(11))],
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn claim_airdrop_with_proof_buffer_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (claimer, 50_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+            (Pubkey::new_unique(), 125_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 3;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        // stage the whole proof in the buffer
+        let (proof_buffer_address, proof_buffer_bump) = Pubkey::find_program_address(
+            &[
+                ProofBuffer::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        let mut proof_buffer_data = ProofBuffer::DISCRIMINATOR.to_vec();
+        proof_buffer_data.extend_from_slice(&[
+            ProofBuffer::VERSION,
+            proof_buffer_bump,
+            proof.len() as u8,
+        ]);
+        for proof_element in &proof {
+            proof_buffer_data.extend_from_slice(proof_element);
+        }
+        let buffer_rent = mollusk
+            .sysvars
+            .rent
+            .minimum_balance(proof_buffer_data.len());
+        let mut proof_buffer_account =
+            AccountSharedData::new(buffer_rent, proof_buffer_data.len(), &PROGRAM_ID);
+        proof_buffer_account.set_data_from_slice(&proof_buffer_data);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: 0,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+                AccountMeta::new(proof_buffer_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                    (proof_buffer_address, proof_buffer_account.into()),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .build(),
+                    Check::account(&proof_buffer_address).closed().build(),
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount - airdrop_recipients[leaf_index].1)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn claim_airdrop_by_operator_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let operator = Pubkey::new_from_array([0x06; 32]);
+        let operator_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        // the recipient never signs
+        let recipient = Pubkey::new_from_array([0x03; 32]);
+        let recipient_account = Account::new(0, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (recipient, 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: operator.to_bytes(),
+            distribution_mode: [AirdropState::MODE_OPERATOR],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 3;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                recipient.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let redirect_address = redirect_of(&airdrop_address, &recipient);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(operator, true),
+                AccountMeta::new(recipient, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (operator, operator_account),
+                    (recipient, recipient_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .data(unsafe {
+                            to_bytes(&ClaimStatus {
+                                discriminator: ClaimStatus::DISCRIMINATOR,
+                                version: [ClaimStatus::VERSION],
+                                bump: [user_claim_account_bump],
+                                revoked: [0],
+                                payer: operator.to_bytes(),
+                            })
+                        })
+                        .build(),
+                    Check::account(&recipient)
+                        .lamports(airdrop_recipients[leaf_index].1)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn claim_airdrop_failure_when_signer_is_not_operator() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let operator = Pubkey::new_from_array([0x06; 32]);
+        let stranger = Pubkey::new_from_array([0x07; 32]);
+        let stranger_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let recipient = Pubkey::new_from_array([0x03; 32]);
+        let recipient_account = Account::new(0, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (recipient, 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: operator.to_bytes(),
+            distribution_mode: [AirdropState::MODE_OPERATOR],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 1;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                recipient.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let redirect_address = redirect_of(&airdrop_address, &recipient);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(stranger, true),
+                AccountMeta::new(recipient, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (stranger, stranger_account),
+                    (recipient, recipient_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+                &[
+                    Check::err(ProgramError::Custom(1)), // unauthorized
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Failure(ProgramError::Custom(1)));
+    }
+
+    #[test]
+    fn test_verify_merkle_proof_rejects_index_beyond_depth() {
+        use pinocchio_airdrop_distributor::utils::{create_airdrop_leaf, verify_merkle_proof};
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (Pubkey::new_unique(), 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let leaf_index = 2;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+        let (recipient, amount) = airdrop_recipients[leaf_index];
+        let leaf = create_airdrop_leaf(&recipient.to_bytes(), amount, 0);
+
+        assert!(verify_merkle_proof(
+            HashFunction::Keccak256,
+            &leaf,
+            &proof,
+            leaf_index as u64,
+            &merkle_root
+        ));
+
+        // the same path with a bit above the depth would be another claim
+        // status when statuses are keyed by leaf index
+        let aliased_index = leaf_index as u64 + (1 << proof.len());
+        assert!(!verify_merkle_proof(
+            HashFunction::Keccak256,
+            &leaf,
+            &proof,
+            aliased_index,
+            &merkle_root
+        ));
+
+        println!("✅ Out-of-range leaf indices are rejected");
+    }
+
+    #[test]
+    fn claim_airdrop_keyed_by_leaf_index_success() {
+        use pinocchio_airdrop_distributor::states::LeafClaimStatus;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        // the claimer holds two leaves from separate snapshot criteria
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (claimer, 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (claimer, 150_000_000u64),
+            (Pubkey::new_unique(), 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_LEAF_INDEX],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 2;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                LeafClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                &(leaf_index as u64).to_le_bytes(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let mut expected_status = LeafClaimStatus::DISCRIMINATOR.to_vec();
+        expected_status.extend_from_slice(&[LeafClaimStatus::VERSION, user_claim_account_bump, 0]);
+        expected_status.extend_from_slice(claimer.as_ref());
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .data(&expected_status)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn claim_airdrop_failure_when_leaf_claimed_again_with_other_bump() {
+        use pinocchio_airdrop_distributor::states::LeafClaimStatus;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        // the claimer holds two leaves from separate snapshot criteria
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (claimer, 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (claimer, 150_000_000u64),
+            (Pubkey::new_unique(), 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_LEAF_INDEX],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 2;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                LeafClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                &(leaf_index as u64).to_le_bytes(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        // the same leaf index at another bump is another address
+        let (other_claim_address, other_claim_bump) = non_canonical_pda(&[
+            LeafClaimStatus::SEED,
+            airdrop_address.as_ref(),
+            &(leaf_index as u64).to_le_bytes(),
+        ]);
+        let other_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let claim = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let replay_data = ClaimAirdropInstructionData {
+            bump: other_claim_bump,
+            ..ix_data
+        };
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&replay_data) });
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let claim_again = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(other_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction_chain(
+                &[
+                    (&claim, &[Check::success()]),
+                    (
+                        &claim_again,
+                        &[Check::err(ProgramError::Custom(11))], // invalid_pda
+                    ),
+                ],
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (other_claim_address, other_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn claim_airdrop_failure_when_claimed_before_migration() {
+        use pinocchio_airdrop_distributor::states::LeafClaimStatus;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (claimer, 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (Pubkey::new_unique(), 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 0;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (claimer_status_address, claimer_status_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        let claimer_status_account = Account::new(0, 0, &system_program);
+
+        let (leaf_status_address, leaf_status_bump) = Pubkey::find_program_address(
+            &[
+                LeafClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                &(leaf_index as u64).to_le_bytes(),
+            ],
+            &PROGRAM_ID,
+        );
+        let leaf_status_account = Account::new(0, 0, &system_program);
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let claim_data = |bump: u8| {
+            let ix_data = ClaimAirdropInstructionData {
+                amount: airdrop_recipients[leaf_index].1,
+                leaf_index: leaf_index as u64,
+                proof_len: proof.len() as u8,
+                bump,
+            };
+            let mut data = vec![1];
+            data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+            for proof_element in &proof {
+                data.extend_from_slice(proof_element);
+            }
+            data
+        };
+
+        // claimed under the claimer key
+        let claim = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &claim_data(claimer_status_bump),
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(claimer_status_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        // the new root comes from a snapshot taken before that claim
+        let mut migrate_data = vec![10];
+        migrate_data.extend_from_slice(&merkle_root);
+        let migrate = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &migrate_data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new_readonly(maker, true),
+            ],
+        );
+
+        // the leaf-keyed status is empty, but the claimer-keyed one is not
+        let claim_again = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &claim_data(leaf_status_bump),
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(leaf_status_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+                AccountMeta::new_readonly(claimer_status_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction_chain(
+                &[
+                    (&claim, &[Check::success()]),
+                    (&migrate, &[Check::success()]),
+                    (
+                        &claim_again,
+                        &[Check::err(ProgramError::Custom(2))], // already_claimed
+                    ),
+                ],
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (maker, maker_account),
+                    (claimer_status_address, claimer_status_account),
+                    (leaf_status_address, leaf_status_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn revoke_leaf_success() {
+        use pinocchio_airdrop_distributor::instructions::RevokeLeafInstructionData;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let recipient = Pubkey::new_from_array([0x03; 32]);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (recipient, 50_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 1;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                recipient.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = RevokeLeafInstructionData {
+            recipient: recipient.to_bytes(),
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            bump: user_claim_account_bump,
+            proof_len: proof.len() as u8,
+        };
+
+        let mut data = vec![11];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(ClaimStatus::LEN);
+        let revoked_status = ClaimStatus {
+            discriminator: ClaimStatus::DISCRIMINATOR,
+            version: [ClaimStatus::VERSION],
+            bump: [user_claim_account_bump],
+            revoked: [1],
+            payer: maker.to_bytes(),
+        };
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .data(unsafe { to_bytes(&revoked_status) })
+                        .build(),
+                    Check::account(&maker)
+                        .lamports(LAMPORTS_PER_SOL - claim_rent + airdrop_recipients[leaf_index].1)
+                        .build(),
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount - airdrop_recipients[leaf_index].1)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn revoke_leaf_failure_when_vault_underfunded() {
+        use pinocchio_airdrop_distributor::instructions::RevokeLeafInstructionData;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let recipient = Pubkey::new_from_array([0x03; 32]);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (recipient, 50_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        // the vault cannot cover the revoked leaf without losing its rent
+        let vault_account = Account::new(vault_rent + 10_000_000, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 1;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                recipient.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = RevokeLeafInstructionData {
+            recipient: recipient.to_bytes(),
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            bump: user_claim_account_bump,
+            proof_len: proof.len() as u8,
+        };
+
+        let mut data = vec![11];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                ],
+                &[Check::err(ProgramError::Custom(19))], // insufficient_funds
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn claim_airdrop_failure_when_leaf_revoked() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (claimer, 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 1;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        // the authority revoked the leaf
+        let user_claim_data = ClaimStatus {
+            discriminator: ClaimStatus::DISCRIMINATOR,
+            version: [ClaimStatus::VERSION],
+            bump: [user_claim_account_bump],
+            revoked: [1],
+            payer: maker.to_bytes(),
+        };
+        let mut user_claim_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(ClaimStatus::LEN),
+            ClaimStatus::LEN,
+            &PROGRAM_ID,
+        );
+        user_claim_account.set_data_from_slice(unsafe { to_bytes(&user_claim_data) });
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account.into()),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+                &[Check::err(ProgramError::Custom(7))], // leaf_revoked
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn claim_airdrop_failure_when_revoked_leaf_claimed_with_other_bump() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (claimer, 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 1;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        // the authority revoked the leaf, writing its marker at the canonical
+        // address; the same claimer at another bump is an address it misses
+        let (other_claim_address, other_claim_bump) = non_canonical_pda(&[
+            ClaimStatus::SEED,
+            airdrop_address.as_ref(),
+            claimer.as_ref(),
+        ]);
+        let other_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: other_claim_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(other_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (other_claim_address, other_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+                &[Check::err(ProgramError::Custom(11))], // invalid_pda
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn reassign_leaf_success() {
+        use pinocchio_airdrop_distributor::instructions::ReassignLeafInstructionData;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        // the old key is lost and does not sign
+        let old_recipient = Pubkey::new_from_array([0x03; 32]);
+        let old_recipient_account = Account::new(0, 0, &system_program);
+        let new_recipient = Pubkey::new_from_array([0x04; 32]);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (redirect_address, redirect_bump) = Pubkey::find_program_address(
+            &[
+                Redirect::SEED,
+                airdrop_address.as_ref(),
+                old_recipient.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ReassignLeafInstructionData {
+            new_recipient: new_recipient.to_bytes(),
+            bump: redirect_bump,
+        };
+
+        let mut data = vec![12];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(maker, true),
+                // the old key is still available and co-signs
+                AccountMeta::new_readonly(old_recipient, true),
+                AccountMeta::new(redirect_address, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let expected_redirect = Redirect {
+            discriminator: Redirect::DISCRIMINATOR,
+            version: [Redirect::VERSION],
+            bump: [redirect_bump],
+            old_recipient: old_recipient.to_bytes(),
+            new_recipient: new_recipient.to_bytes(),
+            signed_by_old_recipient: [1],
+        };
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (maker, maker_account),
+                    (old_recipient, old_recipient_account),
+                    (redirect_address, redirect_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&redirect_address)
+                        .owner(&PROGRAM_ID)
+                        .data(unsafe { to_bytes(&expected_redirect) })
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn reassign_leaf_failure_when_bump_is_not_canonical() {
+        use pinocchio_airdrop_distributor::instructions::ReassignLeafInstructionData;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        // the old key is lost and does not sign
+        let old_recipient = Pubkey::new_from_array([0x03; 32]);
+        let old_recipient_account = Account::new(0, 0, &system_program);
+        let new_recipient = Pubkey::new_from_array([0x04; 32]);
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: [0u8; 32],
+            airdrop_amount: 0u64.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        // a second redirect of the old key, which claims through the
+        // canonical one would never see
+        let (redirect_address, redirect_bump) = non_canonical_pda(&[
+            Redirect::SEED,
+            airdrop_address.as_ref(),
+            old_recipient.as_ref(),
+        ]);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ReassignLeafInstructionData {
+            new_recipient: new_recipient.to_bytes(),
+            bump: redirect_bump,
+        };
+
+        let mut data = vec![12];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(maker, true),
+                // the old key is still available and co-signs
+                AccountMeta::new_readonly(old_recipient, true),
+                AccountMeta::new(redirect_address, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (maker, maker_account),
+                    (old_recipient, old_recipient_account),
+                    (redirect_address, redirect_account),
+                    (system_program, system_account),
+                ],
+                &[Check::err(ProgramError::Custom(11))], // invalid_pda
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn claim_airdrop_with_redirect_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let old_recipient = Pubkey::new_from_array([0x03; 32]);
+        let new_recipient = Pubkey::new_from_array([0x04; 32]);
+        let new_recipient_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (old_recipient, 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (redirect_address, redirect_bump) = Pubkey::find_program_address(
+            &[
+                Redirect::SEED,
+                airdrop_address.as_ref(),
+                old_recipient.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        let redirect_data = Redirect {
+            discriminator: Redirect::DISCRIMINATOR,
+            version: [Redirect::VERSION],
+            bump: [redirect_bump],
+            old_recipient: old_recipient.to_bytes(),
+            new_recipient: new_recipient.to_bytes(),
+            signed_by_old_recipient: [0],
+        };
+        let mut redirect_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(Redirect::LEN),
+            Redirect::LEN,
+            &PROGRAM_ID,
+        );
+        redirect_account.set_data_from_slice(unsafe { to_bytes(&redirect_data) });
+
+        let leaf_index = 1;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        // the claim status stays keyed by the pubkey committed in the leaf
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                old_recipient.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(new_recipient, true),
+                AccountMeta::new(new_recipient, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(ClaimStatus::LEN);
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (new_recipient, new_recipient_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account.into()),
+                    (config_address, config_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .build(),
+                    Check::account(&new_recipient)
+                        .lamports(LAMPORTS_PER_SOL - claim_rent + airdrop_recipients[leaf_index].1)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn claim_airdrop_failure_when_old_recipient_bypasses_redirect() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let old_recipient = Pubkey::new_from_array([0x03; 32]);
+        let new_recipient = Pubkey::new_from_array([0x04; 32]);
+        let old_recipient_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (old_recipient, 50_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (redirect_address, redirect_bump) = Pubkey::find_program_address(
+            &[
+                Redirect::SEED,
+                airdrop_address.as_ref(),
+                old_recipient.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        let redirect_data = Redirect {
+            discriminator: Redirect::DISCRIMINATOR,
+            version: [Redirect::VERSION],
+            bump: [redirect_bump],
+            old_recipient: old_recipient.to_bytes(),
+            new_recipient: new_recipient.to_bytes(),
+            signed_by_old_recipient: [0],
+        };
+        let mut redirect_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(Redirect::LEN),
+            Redirect::LEN,
+            &PROGRAM_ID,
+        );
+        redirect_account.set_data_from_slice(unsafe { to_bytes(&redirect_data) });
+
+        let leaf_index = 1;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        // the claim status stays keyed by the pubkey committed in the leaf
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                old_recipient.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                // the old key signs and passes its own redirect PDA
+                AccountMeta::new(old_recipient, true),
+                AccountMeta::new(old_recipient, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (old_recipient, old_recipient_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account.into()),
+                    (config_address, config_account),
+                ],
+                &[Check::err(ProgramError::Custom(1))], // unauthorized
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn deposit_success() {
+        use pinocchio_airdrop_distributor::instructions::DepositInstructionData;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let _maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        // anyone may top up the vault
+        let depositor = Pubkey::new_from_array([0x05; 32]);
+        let depositor_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+        let claimed = 0u64;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: claimed.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount - claimed, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let deposit = 50_000_000u64;
+        let ix_data = DepositInstructionData { amount: deposit };
+
+        let mut data = vec![13];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(depositor, true),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (depositor, depositor_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount + deposit)
+                        .build(),
+                    Check::account(&depositor)
+                        .lamports(LAMPORTS_PER_SOL - deposit)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+
+        // the deposit does not change what the tree owes
+        let airdrop_account = result.get_account(&airdrop_address).unwrap();
+        let amount_offset = core::mem::offset_of!(AirdropState, airdrop_amount);
+        assert_eq!(
+            airdrop_account.data()[amount_offset..amount_offset + 8],
+            amount.to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn withdraw_excess_success() {
+        use pinocchio_airdrop_distributor::instructions::WithdrawExcessInstructionData;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+        let claimed = 100_000_000u64;
+        let excess = 30_000_000u64;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: claimed.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount - claimed + excess, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let ix_data = WithdrawExcessInstructionData { amount: excess };
+
+        let mut data = vec![14];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount - claimed)
+                        .build(),
+                    Check::account(&maker)
+                        .lamports(LAMPORTS_PER_SOL + excess)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn withdraw_excess_failure_above_outstanding() {
+        use pinocchio_airdrop_distributor::instructions::WithdrawExcessInstructionData;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+        let claimed = 100_000_000u64;
+        let excess = 30_000_000u64;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: claimed.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount - claimed + excess, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        // one lamport more than the excess would dip into unclaimed leaves
+        let ix_data = WithdrawExcessInstructionData { amount: excess + 1 };
+
+        let mut data = vec![14];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                ],
+                &[
+                    Check::err(ProgramError::Custom(19)), // insufficient_funds
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount - claimed + excess)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Failure(ProgramError::Custom(19)));
+    }
+
+    #[test]
+    fn verify_claim_claimable() {
+        use pinocchio_airdrop_distributor::instructions::{
+            VerifyClaimInstructionData, VerifyClaimResult,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (claimer, 50_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 2;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = AccountSharedData::new(0, 0, &system_program);
+
+        let ix_data = VerifyClaimInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            bump: user_claim_account_bump,
+            proof_len: proof.len() as u8,
+        };
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+
+        let mut data = vec![15];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        // read-only: nobody signs and nothing is writable
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new_readonly(claimer, false),
+                AccountMeta::new_readonly(user_claim_address, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            ],
+        );
+
+        let mut expected = vec![VerifyClaimResult::STATUS_CLAIMABLE];
+        expected.extend_from_slice(&airdrop_recipients[leaf_index].1.to_le_bytes());
+        expected.extend_from_slice(&0u64.to_le_bytes());
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account.into()),
+                    (redirect_address, Account::default()),
+                    (Pubkey::new_from_array(Config::ADDRESS), Account::default()),
+                ],
+                &[Check::success(), Check::return_data(&expected)],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn verify_claim_claimable_net_of_protocol_fee() {
+        use pinocchio_airdrop_distributor::instructions::{
+            VerifyClaimInstructionData, VerifyClaimResult,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (claimer, 50_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 2;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = AccountSharedData::new(0, 0, &system_program);
+
+        let ix_data = VerifyClaimInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            bump: user_claim_account_bump,
+            proof_len: proof.len() as u8,
+        };
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+
+        // 2.5% withheld from the amount
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_data = Config {
+            discriminator: Config::DISCRIMINATOR,
+            version: [Config::VERSION],
+            admin: maker.to_bytes(),
+            treasury: Pubkey::new_unique().to_bytes(),
+            fee_mode: [Config::FEE_BPS],
+            fee: 250u64.into(),
+            fee_source: [Config::FEE_FROM_AMOUNT],
+        };
+        let mut config_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(Config::LEN),
+            Config::LEN,
+            &PROGRAM_ID,
+        );
+        config_account.set_data_from_slice(unsafe { to_bytes::<Config>(&config_data) });
+
+        let mut data = vec![15];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        // read-only: nobody signs and nothing is writable
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new_readonly(claimer, false),
+                AccountMeta::new_readonly(user_claim_address, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let mut expected = vec![VerifyClaimResult::STATUS_CLAIMABLE];
+        let fee = airdrop_recipients[leaf_index].1 * 250 / 10_000;
+        expected.extend_from_slice(&(airdrop_recipients[leaf_index].1 - fee).to_le_bytes());
+        expected.extend_from_slice(&fee.to_le_bytes());
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account.into()),
+                    (redirect_address, Account::default()),
+                    (config_address, config_account.into()),
+                ],
+                &[Check::success(), Check::return_data(&expected)],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn verify_claim_redirected() {
+        use pinocchio_airdrop_distributor::instructions::{
+            VerifyClaimInstructionData, VerifyClaimResult,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (claimer, 50_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 2;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = AccountSharedData::new(0, 0, &system_program);
+
+        let ix_data = VerifyClaimInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            bump: user_claim_account_bump,
+            proof_len: proof.len() as u8,
+        };
+
+        // the leaves of the claimer were reassigned to a new key
+        let (redirect_address, redirect_bump) = Pubkey::find_program_address(
+            &[Redirect::SEED, airdrop_address.as_ref(), claimer.as_ref()],
+            &PROGRAM_ID,
+        );
+        let redirect_data = Redirect {
+            discriminator: Redirect::DISCRIMINATOR,
+            version: [Redirect::VERSION],
+            bump: [redirect_bump],
+            old_recipient: claimer.to_bytes(),
+            new_recipient: Pubkey::new_from_array([0x04; 32]).to_bytes(),
+            signed_by_old_recipient: [0],
+        };
+        let mut redirect_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(Redirect::LEN),
+            Redirect::LEN,
+            &PROGRAM_ID,
+        );
+        redirect_account.set_data_from_slice(unsafe { to_bytes(&redirect_data) });
+
+        let mut data = vec![15];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        // read-only: nobody signs and nothing is writable
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new_readonly(claimer, false),
+                AccountMeta::new_readonly(user_claim_address, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            ],
+        );
+
+        // nothing claimable and no fee
+        let mut expected = vec![VerifyClaimResult::STATUS_REDIRECTED];
+        expected.extend_from_slice(&[0u8; 16]);
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account.into()),
+                    (redirect_address, redirect_account.into()),
+                    (Pubkey::new_from_array(Config::ADDRESS), Account::default()),
+                ],
+                &[Check::success(), Check::return_data(&expected)],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn verify_claim_already_claimed() {
+        use pinocchio_airdrop_distributor::instructions::{
+            VerifyClaimInstructionData, VerifyClaimResult,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (claimer, 50_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+        let claim_rent = mollusk.sysvars.rent.minimum_balance(ClaimStatus::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let leaf_index = 2;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_data = ClaimStatus {
+            discriminator: ClaimStatus::DISCRIMINATOR,
+            version: [ClaimStatus::VERSION],
+            bump: [user_claim_account_bump],
+            revoked: [0],
+            payer: claimer.to_bytes(),
+        };
+        let mut user_claim_account =
+            AccountSharedData::new(claim_rent, ClaimStatus::LEN, &PROGRAM_ID);
+        user_claim_account.set_data_from_slice(unsafe { to_bytes(&user_claim_data) });
+
+        let ix_data = VerifyClaimInstructionData {
+            amount: airdrop_recipients[leaf_index].1,
+            leaf_index: leaf_index as u64,
+            bump: user_claim_account_bump,
+            proof_len: proof.len() as u8,
+        };
+
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+
+        let mut data = vec![15];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        // add proof to data
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        // read-only: nobody signs and nothing is writable
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new_readonly(claimer, false),
+                AccountMeta::new_readonly(user_claim_address, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            ],
+        );
+
+        // already claimed: nothing left to claim
+        // nothing claimable and no fee
+        let mut expected = vec![VerifyClaimResult::STATUS_ALREADY_CLAIMED];
+        expected.extend_from_slice(&[0u8; 16]);
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account.into()),
+                    (redirect_address, Account::default()),
+                    (Pubkey::new_from_array(Config::ADDRESS), Account::default()),
+                ],
+                &[Check::success(), Check::return_data(&expected)],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn set_metadata_success() {
+        use pinocchio_airdrop_distributor::{
+            instructions::SetMetadataInstructionData, states::Metadata,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (metadata_address, metadata_bump) =
+            Pubkey::find_program_address(&[Metadata::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+        let metadata_account = Account::new(0, 0, &system_program);
+        let metadata_rent = mollusk.sysvars.rent.minimum_balance(Metadata::LEN);
+
+        let mut name = [0u8; Metadata::MAX_NAME_LEN];
+        name[..11].copy_from_slice(b"Test Season");
+        let mut uri = [0u8; Metadata::MAX_URI_LEN];
+        uri[..30].copy_from_slice(b"https://example.com/tree.json/");
+
+        let ix_data = SetMetadataInstructionData {
+            bump: metadata_bump,
+            name,
+            uri,
+            tree_hash: [0x42; 32],
+            leaf_count: airdrop_recipients.len() as u64,
+            depth: 2,
+        };
+
+        let mut data = vec![16];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new(metadata_address, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (maker, maker_account),
+                    (metadata_address, metadata_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&metadata_address)
+                        .owner(&PROGRAM_ID)
+                        .lamports(metadata_rent)
+                        .space(Metadata::LEN)
+                        .build(),
+                    Check::account(&maker)
+                        .lamports(LAMPORTS_PER_SOL - metadata_rent)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+
+        let metadata_account = result.get_account(&metadata_address).unwrap();
+        let name_offset = core::mem::offset_of!(Metadata, name);
+        assert_eq!(
+            &metadata_account.data()[name_offset..name_offset + Metadata::MAX_NAME_LEN],
+            &name
+        );
+        let leaf_count_offset = core::mem::offset_of!(Metadata, leaf_count);
+        assert_eq!(
+            metadata_account.data()[leaf_count_offset..leaf_count_offset + 8],
+            3u64.to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn set_metadata_failure_when_bump_is_not_canonical() {
+        use pinocchio_airdrop_distributor::{
+            instructions::SetMetadataInstructionData, states::Metadata,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        // a second metadata account of the campaign, at another bump
+        let (metadata_address, metadata_bump) =
+            non_canonical_pda(&[Metadata::SEED, airdrop_address.as_ref()]);
+        let metadata_account = Account::new(0, 0, &system_program);
+
+        let mut name = [0u8; Metadata::MAX_NAME_LEN];
+        name[..11].copy_from_slice(b"Test Season");
+        let mut uri = [0u8; Metadata::MAX_URI_LEN];
+        uri[..30].copy_from_slice(b"https://example.com/tree.json/");
+
+        let ix_data = SetMetadataInstructionData {
+            bump: metadata_bump,
+            name,
+            uri,
+            tree_hash: [0x42; 32],
+            leaf_count: airdrop_recipients.len() as u64,
+            depth: 2,
+        };
+
+        let mut data = vec![16];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new_readonly(airdrop_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new(metadata_address, false),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (maker, maker_account),
+                    (metadata_address, metadata_account),
+                    (system_program, system_account),
+                ],
+                &[Check::err(ProgramError::Custom(11))], // invalid_pda
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[test]
+    fn migrate_state_from_v0() {
+        use pinocchio_airdrop_distributor::{
+            instructions::MigrateStateInstructionData, states::AirdropStateV0,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+        let claimed = airdrop_recipients[0].1;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+        let vault_account = Account::new(0, 0, &system_program);
+
+        // the first deployment stored the claimed amount big-endian
+        let airdrop_account_data = AirdropStateV0 {
+            merkle_root,
+            authority: maker.to_bytes(),
+            airdrop_amount: amount.to_le_bytes(),
+            amount_claimed: claimed.to_be_bytes(),
+            bump: [airdrop_account_bump],
+        };
+        let v0_rent = mollusk.sysvars.rent.minimum_balance(AirdropStateV0::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(v0_rent + amount - claimed, AirdropStateV0::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropStateV0>(&airdrop_account_data) });
+
+        let ix_data = MigrateStateInstructionData { vault_bump };
+
+        let mut data = vec![17];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+
+        let expected_state = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: claimed.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&airdrop_address)
+                        .lamports(lamport_for_rent)
+                        .space(AirdropState::LEN)
+                        .data(unsafe { to_bytes::<AirdropState>(&expected_state) })
+                        .build(),
+                    Check::account(&vault_address)
+                        .owner(&PROGRAM_ID)
+                        .lamports(vault_rent + amount - claimed)
+                        .build(),
+                    Check::account(&maker)
+                        .lamports(LAMPORTS_PER_SOL - vault_rent - (lamport_for_rent - v0_rent))
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn test_error_codes_round_trip() {
+        use pinocchio::program_error::ToStr;
+        use pinocchio_airdrop_distributor::errors::AirdropProgramError;
+
+        let last = AirdropProgramError::CpiNotAllowed as u32;
+        for code in 0..=last + 1 {
+            match AirdropProgramError::try_from(code) {
+                Ok(error) => {
+                    assert_eq!(error as u32, code);
+                    assert!(error.to_str::<AirdropProgramError>().starts_with("Error: "));
+                }
+                // the unassigned codes and anything past the last one
+                Err(_) => assert!(matches!(code, 16 | 17) || code > last),
+            }
+        }
+        assert_eq!(AirdropProgramError::InvalidPda as u32, 11);
+    }
+
+    #[test]
+    fn test_zero_copy_state_from_bytes() {
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: [0x02; 32],
+            merkle_root: [0x03; 32],
+            airdrop_amount: 300u64.into(),
+            amount_claimed: 100u64.into(),
+            bump: [255],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [254],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let mut bytes = unsafe { to_bytes(&airdrop_account_data) }.to_vec();
+
+        let airdrop_state = AirdropState::from_bytes(&bytes).unwrap();
+        assert_eq!(airdrop_state.airdrop_amount.get(), 300);
+        assert_eq!(airdrop_state.outstanding(), Some(200));
+
+        // any offset works: every field has alignment 1
+        let mut shifted = vec![0u8; 1];
+        shifted.extend_from_slice(&bytes);
+        assert!(AirdropState::from_bytes(&shifted[1..]).is_ok());
+
+        assert!(AirdropState::from_bytes(&bytes[1..]).is_err());
+        assert!(ClaimStatus::from_bytes(&bytes).is_err());
+
+        AirdropState::from_bytes_mut(&mut bytes)
+            .unwrap()
+            .amount_claimed
+            .set(250);
+        let amount_offset = core::mem::offset_of!(AirdropState, amount_claimed);
+        assert_eq!(
+            bytes[amount_offset..amount_offset + 8],
+            250u64.to_le_bytes()
+        );
+
+        bytes[0] ^= 1;
+        assert_eq!(
+            AirdropState::from_bytes(&bytes).err(),
+            Some(pinocchio::program_error::ProgramError::Custom(13))
+        );
+    }
+
+    #[cfg(feature = "anchor-discriminators")]
+    #[test]
+    fn update_merkle_tree_with_anchor_discriminator() {
+        use pinocchio_airdrop_distributor::instructions::UpdateMerkleRootAirdrop;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let old_airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+            (Pubkey::new_unique(), 125_000_000u64),
+        ];
+        let old_merkle_root = create_merkle_root(&old_airdrop_recipients);
+        let old_amount: u64 = old_airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root: old_merkle_root,
+            airdrop_amount: old_amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + old_amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let new_airdrop_recipients = vec![
+            (Pubkey::new_unique(), 300_000_000u64),
+            (Pubkey::new_unique(), 20_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+            (Pubkey::new_unique(), 720_000_000u64),
+            (Pubkey::new_unique(), 150_000_000u64),
+        ];
+        let new_merkle_root = create_merkle_root(&new_airdrop_recipients);
+        let new_amount: u64 = new_airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let ix_data = UpdateMerkleRootInstructionData {
+            new_merkle_root,
+            additional_amount: new_amount - old_amount,
+        };
+
+        let mut data = UpdateMerkleRootAirdrop::ANCHOR_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&airdrop_address).owner(&PROGRAM_ID).build(),
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + new_amount)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn claim_airdrop_by_claim_authority_success() {
+        use pinocchio_airdrop_distributor::utils::hash_pair;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        // a PDA of another program receives the funds and never signs
+        let staking_program = Pubkey::new_unique();
+        let (beneficiary, _) = Pubkey::find_program_address(&[b"stake_pool"], &staking_program);
+        let beneficiary_account = Account::new(0, 0, &staking_program);
+
+        let claim_authority = Pubkey::new_from_array([0x07; 32]);
+        let claim_authority_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let claim_amount = 50_000_000u64;
+        let hash_fn = HashFunction::Keccak256;
+        let leaf = hash_fn
+            .create_delegated_airdrop_leaf(
+                &beneficiary.to_bytes(),
+                &claim_authority.to_bytes(),
+                claim_amount,
+                0,
+            )
+            .unwrap();
+        let sibling = hash_fn
+            .create_airdrop_leaf(&Pubkey::new_unique().to_bytes(), 100_000_000, 0)
+            .unwrap();
+        let merkle_root = hash_pair(&leaf, &sibling);
+        let amount = claim_amount + 100_000_000;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_CLAIM_AUTHORITY],
+            hash_fn: [hash_fn as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        // the claim status is keyed by the beneficiary
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                beneficiary.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: claim_amount,
+            leaf_index: 0,
+            proof_len: 1,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+        data.extend_from_slice(&sibling);
+
+        let redirect_address = redirect_of(&airdrop_address, &beneficiary);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_account = Account::new(0, 0, &system_program);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claim_authority, true),
+                AccountMeta::new(beneficiary, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claim_authority, claim_authority_account),
+                    (beneficiary, beneficiary_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .build(),
+                    Check::account(&beneficiary).lamports(claim_amount).build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn migrate_state_from_v1() {
+        use pinocchio_airdrop_distributor::{
+            instructions::MigrateStateInstructionData, states::AirdropStateV1,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropStateV1 {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [1],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+        };
+        let v1_rent = mollusk.sysvars.rent.minimum_balance(AirdropStateV1::LEN);
+
+        let mut airdrop_account = AccountSharedData::new(v1_rent, AirdropStateV1::LEN, &PROGRAM_ID);
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropStateV1>(&airdrop_account_data) });
+
+        let ix_data = MigrateStateInstructionData { vault_bump };
+
+        let mut data = vec![17];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(maker, true),
+                AccountMeta::new_readonly(system_program, false),
+            ],
+        );
+
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        // the v1 fields are kept as they were, the CPI policy allows all
+        let expected_state = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (maker, maker_account),
+                    (system_program, system_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&airdrop_address)
+                        .lamports(lamport_for_rent)
+                        .space(AirdropState::LEN)
+                        .data(unsafe { to_bytes::<AirdropState>(&expected_state) })
+                        .build(),
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount)
+                        .build(),
+                    Check::account(&maker)
+                        .lamports(LAMPORTS_PER_SOL - (lamport_for_rent - v1_rent))
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn set_cpi_policy_success() {
+        use pinocchio_airdrop_distributor::instructions::SetCpiPolicyInstructionData;
+
+        let mollusk = get_mollusk();
+
+        let (system_program, _system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+        let maker_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (Pubkey::new_unique(), 200_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (_vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let mut airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        // only a staking program may claim through CPI
+        let staking_program = Pubkey::new_unique();
+        let mut cpi_callers = [[0u8; 32]; AirdropState::MAX_CPI_CALLERS];
+        cpi_callers[0] = staking_program.to_bytes();
+
+        let ix_data = SetCpiPolicyInstructionData {
+            cpi_policy: AirdropState::CPI_ALLOWLIST,
+            cpi_caller_count: 1,
+            cpi_callers,
+        };
+
+        let mut data = vec![18];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new_readonly(maker, true),
+            ],
+        );
+
+        airdrop_account_data.cpi_policy = [AirdropState::CPI_ALLOWLIST];
+        airdrop_account_data.cpi_caller_count = [1];
+        airdrop_account_data.cpi_callers = cpi_callers;
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (maker, maker_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&airdrop_address)
+                        .data(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) })
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn test_config_address() {
+        assert_eq!(
+            Pubkey::find_program_address(&[Config::SEED], &PROGRAM_ID),
+            (Pubkey::new_from_array(Config::ADDRESS), Config::BUMP)
+        );
+    }
+
+    #[test]
+    fn claim_airdrop_with_protocol_fee_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let treasury = Pubkey::new_from_array([0x04; 32]);
+        let treasury_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (claimer, 50_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        // 2.5% withheld from every claimed amount
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_data = Config {
+            discriminator: Config::DISCRIMINATOR,
+            version: [Config::VERSION],
+            admin: maker.to_bytes(),
+            treasury: treasury.to_bytes(),
+            fee_mode: [Config::FEE_BPS],
+            fee: 250u64.into(),
+            fee_source: [Config::FEE_FROM_AMOUNT],
+        };
+        let mut config_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(Config::LEN),
+            Config::LEN,
+            &PROGRAM_ID,
+        );
+        config_account.set_data_from_slice(unsafe { to_bytes::<Config>(&config_data) });
+
+        let leaf_index = 1;
+        let claimed = airdrop_recipients[leaf_index].1;
+        let fee = claimed * 250 / 10_000;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimAirdropInstructionData {
+            amount: claimed,
+            leaf_index: leaf_index as u64,
+            proof_len: proof.len() as u8,
+            bump: user_claim_account_bump,
+        };
+
+        let mut data = vec![1];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+        for proof_element in &proof {
+            data.extend_from_slice(proof_element);
+        }
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(claimer, true),
+                AccountMeta::new(claimer, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(redirect_address, false),
+                AccountMeta::new_readonly(config_address, false),
+                AccountMeta::new(treasury, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account.into()),
+                    (treasury, treasury_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount - claimed)
+                        .build(),
+                    Check::account(&treasury)
+                        .lamports(LAMPORTS_PER_SOL + fee)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[test]
+    fn test_create_merkle_root_and_proof() {
+        use pinocchio_airdrop_distributor::utils::{create_airdrop_leaf, verify_merkle_proof};
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 1000u64),
+            (Pubkey::new_unique(), 2000u64),
+            (Pubkey::new_unique(), 1500u64),
+            (Pubkey::new_unique(), 3000u64),
+        ];
+
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        println!(
+            "Generated merkle root (first 8 bytes): {:?}",
+            &merkle_root[..8]
+        );
+
+        let empty_root = create_merkle_root(&[]);
+        assert_eq!(empty_root, [0u8; 32]);
+
+        let single_recipient = vec![(Pubkey::new_unique(), 5000u64)];
+        let single_root = create_merkle_root(&single_recipient);
+
+        let expected_leaf =
+            create_airdrop_leaf(&single_recipient[0].0.to_bytes(), single_recipient[0].1, 0);
+        assert_eq!(single_root, expected_leaf);
+
+        for (index, (pubkey, amount)) in airdrop_recipients.iter().enumerate() {
+            let proof = create_merkle_proof(&airdrop_recipients, index);
+            let leaf = create_airdrop_leaf(&pubkey.to_bytes(), *amount, 0);
+
+            // Verify proof
+            let is_valid = verify_merkle_proof(
+                HashFunction::Keccak256,
+                &leaf,
+                &proof,
+                index as u64,
+                &merkle_root,
+            );
+            assert!(is_valid, "Proof verification failed for index {}", index);
+        }
+
+        println!("✅ Merkle root creation and proof verification successful");
+    }
+
+    #[test]
+    fn test_verify_merkle_multiproof_rejects_lone_leaf_with_nonzero_index() {
+        use pinocchio_airdrop_distributor::utils::{create_airdrop_leaf, verify_merkle_multiproof};
+
+        // a single-leaf tree: the leaf is the root and has no proof
+        let (recipient, amount) = (Pubkey::new_unique(), 100_000_000u64);
+        let leaf = create_airdrop_leaf(&recipient.to_bytes(), amount, 0);
+        let merkle_root = create_merkle_root(&[(recipient, amount)]);
+
+        assert!(verify_merkle_multiproof(
+            HashFunction::Keccak256,
+            &[leaf],
+            &[0],
+            &[],
+            &[],
+            &merkle_root
+        ));
+
+        // any other index would be another leaf-keyed claim status
+        for leaf_index in [1u64, 7, u64::MAX] {
+            assert!(!verify_merkle_multiproof(
+                HashFunction::Keccak256,
+                &[leaf],
+                &[leaf_index],
+                &[],
+                &[],
+                &merkle_root
+            ));
+        }
+    }
+
+    #[test]
+    fn test_create_merkle_multiproof() {
+        use pinocchio_airdrop_distributor::utils::{create_airdrop_leaf, verify_merkle_multiproof};
+
+        let airdrop_recipients: Vec<(Pubkey, u64)> = (0..7)
+            .map(|i| (Pubkey::new_unique(), 1000u64 * (i + 1)))
+            .collect();
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+
+        let leaf_of = |index: usize| {
+            let (pubkey, amount) = &airdrop_recipients[index];
+            create_airdrop_leaf(&pubkey.to_bytes(), *amount, 0)
+        };
+
+        for targets in [
+            vec![0usize],
+            vec![6],
+            vec![0, 1],
+            vec![1, 2, 5],
+            vec![3, 4, 6],
+            (0..7).collect::<Vec<_>>(),
+        ] {
+            let (proof, proof_flags) = create_merkle_multiproof(&airdrop_recipients, &targets);
+            let leaves: Vec<[u8; 32]> = targets.iter().map(|index| leaf_of(*index)).collect();
+            let indices: Vec<u64> = targets.iter().map(|index| *index as u64).collect();
+
+            assert!(
+                verify_merkle_multiproof(
+                    HashFunction::Keccak256,
+                    &leaves,
+                    &indices,
+                    &proof,
+                    &proof_flags,
+                    &merkle_root
+                ),
+                "Multiproof verification failed for {:?}",
+                targets
+            );
+        }
+
+        // wrong index, swapped order and tampered proof must all fail
+        let (proof, proof_flags) = create_merkle_multiproof(&airdrop_recipients, &[1, 2, 5]);
+        let leaves = [leaf_of(1), leaf_of(2), leaf_of(5)];
+        assert!(!verify_merkle_multiproof(
+            HashFunction::Keccak256,
+            &leaves,
+            &[1, 3, 5],
+            &proof,
+            &proof_flags,
+            &merkle_root
+        ));
+        assert!(!verify_merkle_multiproof(
+            HashFunction::Keccak256,
+            &[leaf_of(2), leaf_of(1), leaf_of(5)],
+            &[2, 1, 5],
+            &proof,
+            &proof_flags,
+            &merkle_root
+        ));
+        let mut tampered = proof.clone();
+        tampered[0][0] ^= 1;
+        assert!(!verify_merkle_multiproof(
+            HashFunction::Keccak256,
+            &leaves,
+            &[1, 2, 5],
+            &tampered,
+            &proof_flags,
+            &merkle_root
+        ));
+
+        // the single proofs still agree with the root for odd-sized levels
+        for index in 0..airdrop_recipients.len() {
+            let proof = create_merkle_proof(&airdrop_recipients, index);
+            assert!(
+                pinocchio_airdrop_distributor::utils::verify_merkle_proof(
+                    HashFunction::Keccak256,
+                    &leaf_of(index),
+                    &proof,
+                    index as u64,
+                    &merkle_root
+                ),
+                "Proof verification failed for index {}",
+                index
+            );
+        }
+
+        println!("✅ Merkle multiproof creation and verification successful");
+    }
+
+    #[cfg(feature = "sha256")]
+    #[test]
+    fn test_sha256_hash_function() {
+        use pinocchio_airdrop_distributor::utils::verify_merkle_proof;
+        use solana_sdk::hash::hashv;
+
+        let hash_fn = HashFunction::Sha256;
+
+        let left = [1u8; 32];
+        let right = [2u8; 32];
+        assert_eq!(
+            hash_fn.hash_pair(&left, &right).unwrap(),
+            hashv(&[&left, &right]).to_bytes()
+        );
+
+        let recipient = [42u8; 32];
+        let amount = 1000u64;
+        let leaf = hash_fn.create_airdrop_leaf(&recipient, amount, 0).unwrap();
+        assert_eq!(
+            leaf,
+            hashv(&[&recipient, &amount.to_le_bytes(), &[0u8]]).to_bytes()
+        );
+
+        let sibling = hash_fn.create_airdrop_leaf(&[7u8; 32], 5, 0).unwrap();
+        let root = hash_fn.hash_pair(&sibling, &leaf).unwrap();
+        assert!(verify_merkle_proof(hash_fn, &leaf, &[sibling], 1, &root));
+        assert!(!verify_merkle_proof(hash_fn, &leaf, &[sibling], 0, &root));
+
+        println!("✅ SHA-256 hash function works correctly");
+    }
+
+    #[cfg(feature = "poseidon")]
+    #[test]
+    fn test_poseidon_hash_function() {
+        use pinocchio_airdrop_distributor::utils::verify_merkle_proof;
+
+        let hash_fn = HashFunction::Poseidon;
+
+        // a pubkey with every bit set is above the BN254 modulus as one element
+        let recipient = [0xffu8; 32];
+        let leaf = hash_fn.create_airdrop_leaf(&recipient, 1000, 0).unwrap();
+        let sibling = hash_fn.create_airdrop_leaf(&[7u8; 32], 5, 0).unwrap();
+        let root = hash_fn.hash_pair(&leaf, &sibling).unwrap();
+
+        assert!(verify_merkle_proof(hash_fn, &leaf, &[sibling], 0, &root));
+        assert!(!verify_merkle_proof(hash_fn, &leaf, &[sibling], 1, &root));
+
+        // proof elements outside the field are rejected instead of panicking
+        assert!(!verify_merkle_proof(
+            hash_fn,
+            &leaf,
+            &[[0xffu8; 32]],
+            0,
+            &root
+        ));
+
+        println!("✅ Poseidon hash function works correctly");
+    }
+
+    #[cfg(feature = "evm")]
+    fn evm_address_of(secret_key: &libsecp256k1::SecretKey) -> [u8; 20] {
+        let public_key = libsecp256k1::PublicKey::from_secret_key(secret_key);
+        let hash = solana_sdk::keccak::hash(&public_key.serialize()[1..]);
+        hash.to_bytes()[12..].try_into().unwrap()
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn test_recover_evm_address() {
+        use pinocchio_airdrop_distributor::{
+            instructions::evm_claim_message_hash, utils::recover_evm_address,
+        };
+
+        let secret_key = libsecp256k1::SecretKey::parse(&[0x11; 32]).unwrap();
+        let address = evm_address_of(&secret_key);
+
+        let airdrop = [0x01; 32];
+        let destination = [0x02; 32];
+        let message_hash = evm_claim_message_hash(&airdrop, &destination, 1000);
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&message_hash), &secret_key);
+
+        let recovered = recover_evm_address(
+            &message_hash,
+            recovery_id.serialize(),
+            &signature.serialize(),
+        );
+        assert_eq!(recovered, Some(address));
+
+        // Ethereum-style v = 27/28 is accepted too
+        let recovered = recover_evm_address(
+            &message_hash,
+            recovery_id.serialize() + 27,
+            &signature.serialize(),
+        );
+        assert_eq!(recovered, Some(address));
+
+        // a different destination recovers a different signer
+        let other_hash = evm_claim_message_hash(&airdrop, &[0x03; 32], 1000);
+        let recovered =
+            recover_evm_address(&other_hash, recovery_id.serialize(), &signature.serialize());
+        assert_ne!(recovered, Some(address));
+
+        println!("✅ EVM address recovery works correctly");
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn claim_evm_success() {
+        use pinocchio_airdrop_distributor::instructions::{
+            evm_claim_message_hash, ClaimEvmInstructionData,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let payer = Pubkey::new_from_array([0x06; 32]);
+        let payer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let destination = Pubkey::new_from_array([0x03; 32]);
+        let destination_account = Account::new(0, 0, &system_program);
+
+        let secret_key = libsecp256k1::SecretKey::parse(&[0x11; 32]).unwrap();
+        let address = evm_address_of(&secret_key);
+
+        let hash_fn = HashFunction::Keccak256;
+        let claim_amount = 50_000_000u64;
+        let evm_leaf = hash_fn
+            .create_evm_airdrop_leaf(&address, claim_amount, 0)
+            .unwrap();
+        let other_leaf = hash_fn
+            .create_airdrop_leaf(&Pubkey::new_unique().to_bytes(), 100_000_000, 0)
+            .unwrap();
+        let merkle_root = hash_fn.hash_pair(&other_leaf, &evm_leaf).unwrap();
+        let amount = claim_amount + 100_000_000;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::EVM_SEED,
+                airdrop_address.as_ref(),
+                address.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let message_hash = evm_claim_message_hash(
+            &airdrop_address.to_bytes(),
+            &destination.to_bytes(),
+            claim_amount,
+        );
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&message_hash), &secret_key);
+
+        let ix_data = ClaimEvmInstructionData {
+            amount: claim_amount,
+            leaf_index: 1,
+            signature: signature.serialize(),
+            recovery_id: recovery_id.serialize(),
+            bump: user_claim_account_bump,
+            proof_len: 1,
+        };
+
+        let mut data = vec![7];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+        data.extend_from_slice(&other_leaf);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (payer, payer_account),
+                    (destination, destination_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (Pubkey::new_from_array(Config::ADDRESS), Account::default()),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .data(unsafe {
+                            to_bytes(&ClaimStatus {
+                                discriminator: ClaimStatus::DISCRIMINATOR,
+                                version: [ClaimStatus::VERSION],
+                                bump: [user_claim_account_bump],
+                                revoked: [0],
+                                payer: payer.to_bytes(),
+                            })
+                        })
+                        .build(),
+                    Check::account(&destination).lamports(claim_amount).build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn claim_evm_failure_when_bump_is_not_canonical() {
+        use pinocchio_airdrop_distributor::instructions::{
+            evm_claim_message_hash, ClaimEvmInstructionData,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let payer = Pubkey::new_from_array([0x06; 32]);
+        let payer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let destination = Pubkey::new_from_array([0x03; 32]);
+        let destination_account = Account::new(0, 0, &system_program);
+
+        let secret_key = libsecp256k1::SecretKey::parse(&[0x11; 32]).unwrap();
+        let address = evm_address_of(&secret_key);
+
+        let hash_fn = HashFunction::Keccak256;
+        let claim_amount = 50_000_000u64;
+        let evm_leaf = hash_fn
+            .create_evm_airdrop_leaf(&address, claim_amount, 0)
+            .unwrap();
+        let other_leaf = hash_fn
+            .create_airdrop_leaf(&Pubkey::new_unique().to_bytes(), 100_000_000, 0)
+            .unwrap();
+        let merkle_root = hash_fn.hash_pair(&other_leaf, &evm_leaf).unwrap();
+        let amount = claim_amount + 100_000_000;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        // an off-curve address of the same seeds, which a canonical status
+        // of the address would not block
+        let (user_claim_address, user_claim_account_bump) = non_canonical_pda(&[
+            ClaimStatus::EVM_SEED,
+            airdrop_address.as_ref(),
+            address.as_ref(),
+        ]);
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let message_hash = evm_claim_message_hash(
+            &airdrop_address.to_bytes(),
+            &destination.to_bytes(),
+            claim_amount,
+        );
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&message_hash), &secret_key);
+
+        let ix_data = ClaimEvmInstructionData {
+            amount: claim_amount,
+            leaf_index: 1,
+            signature: signature.serialize(),
+            recovery_id: recovery_id.serialize(),
+            bump: user_claim_account_bump,
+            proof_len: 1,
+        };
+
+        let mut data = vec![7];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+        data.extend_from_slice(&other_leaf);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (payer, payer_account),
+                    (destination, destination_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (Pubkey::new_from_array(Config::ADDRESS), Account::default()),
+                ],
+                &[Check::err(ProgramError::Custom(11))], // invalid_pda
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn claim_evm_with_protocol_fee_success() {
+        use pinocchio_airdrop_distributor::instructions::{
+            evm_claim_message_hash, ClaimEvmInstructionData,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let payer = Pubkey::new_from_array([0x06; 32]);
+        let payer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let treasury = Pubkey::new_from_array([0x04; 32]);
+        let treasury_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let destination = Pubkey::new_from_array([0x03; 32]);
+        let destination_account = Account::new(0, 0, &system_program);
+
+        let secret_key = libsecp256k1::SecretKey::parse(&[0x11; 32]).unwrap();
+        let address = evm_address_of(&secret_key);
+
+        let hash_fn = HashFunction::Keccak256;
+        let claim_amount = 50_000_000u64;
+        let evm_leaf = hash_fn
+            .create_evm_airdrop_leaf(&address, claim_amount, 0)
+            .unwrap();
+        let other_leaf = hash_fn
+            .create_airdrop_leaf(&Pubkey::new_unique().to_bytes(), 100_000_000, 0)
+            .unwrap();
+        let merkle_root = hash_fn.hash_pair(&other_leaf, &evm_leaf).unwrap();
+        let amount = claim_amount + 100_000_000;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::EVM_SEED,
+                airdrop_address.as_ref(),
+                address.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let message_hash = evm_claim_message_hash(
+            &airdrop_address.to_bytes(),
+            &destination.to_bytes(),
+            claim_amount,
+        );
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&message_hash), &secret_key);
+
+        let ix_data = ClaimEvmInstructionData {
+            amount: claim_amount,
+            leaf_index: 1,
+            signature: signature.serialize(),
+            recovery_id: recovery_id.serialize(),
+            bump: user_claim_account_bump,
+            proof_len: 1,
+        };
+
+        // flat fee paid by the payer on top of the amount
+        let fee = 1_000_000u64;
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_data = Config {
+            discriminator: Config::DISCRIMINATOR,
+            version: [Config::VERSION],
+            admin: maker.to_bytes(),
+            treasury: treasury.to_bytes(),
+            fee_mode: [Config::FEE_FLAT],
+            fee: fee.into(),
+            fee_source: [Config::FEE_FROM_CLAIMER],
+        };
+        let mut config_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(Config::LEN),
+            Config::LEN,
+            &PROGRAM_ID,
+        );
+        config_account.set_data_from_slice(unsafe { to_bytes::<Config>(&config_data) });
+
+        let mut data = vec![7];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+        data.extend_from_slice(&other_leaf);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(config_address, false),
+                AccountMeta::new(treasury, false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (payer, payer_account),
+                    (destination, destination_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (config_address, config_account.into()),
+                    (treasury, treasury_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .build(),
+                    Check::account(&destination).lamports(claim_amount).build(),
+                    Check::account(&treasury)
+                        .lamports(LAMPORTS_PER_SOL + fee)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[cfg(feature = "evm")]
+    #[test]
+    fn claim_evm_failure_without_instructions_sysvar() {
+        use pinocchio_airdrop_distributor::instructions::{
+            evm_claim_message_hash, ClaimEvmInstructionData,
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let payer = Pubkey::new_from_array([0x06; 32]);
+        let payer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let destination = Pubkey::new_from_array([0x03; 32]);
+        let destination_account = Account::new(0, 0, &system_program);
+
+        let secret_key = libsecp256k1::SecretKey::parse(&[0x11; 32]).unwrap();
+        let address = evm_address_of(&secret_key);
+
+        let hash_fn = HashFunction::Keccak256;
+        let claim_amount = 50_000_000u64;
+        let evm_leaf = hash_fn
+            .create_evm_airdrop_leaf(&address, claim_amount, 0)
+            .unwrap();
+        let other_leaf = hash_fn
+            .create_airdrop_leaf(&Pubkey::new_unique().to_bytes(), 100_000_000, 0)
+            .unwrap();
+        let merkle_root = hash_fn.hash_pair(&other_leaf, &evm_leaf).unwrap();
+        let amount = claim_amount + 100_000_000;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            // no claims through CPI, which needs the instructions sysvar
+            cpi_policy: [AirdropState::CPI_DENY],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::EVM_SEED,
+                airdrop_address.as_ref(),
+                address.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        let message_hash = evm_claim_message_hash(
+            &airdrop_address.to_bytes(),
+            &destination.to_bytes(),
+            claim_amount,
+        );
+        let (signature, recovery_id) =
+            libsecp256k1::sign(&libsecp256k1::Message::parse(&message_hash), &secret_key);
+
+        let ix_data = ClaimEvmInstructionData {
+            amount: claim_amount,
+            leaf_index: 1,
+            signature: signature.serialize(),
+            recovery_id: recovery_id.serialize(),
+            bump: user_claim_account_bump,
+            proof_len: 1,
+        };
+
+        let mut data = vec![7];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+        data.extend_from_slice(&other_leaf);
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(user_claim_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (payer, payer_account),
+                    (destination, destination_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (Pubkey::new_from_array(Config::ADDRESS), Account::default()),
+                ],
+                &[Check::err(ProgramError::NotEnoughAccountKeys)],
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    /// Verifying key and proof that satisfy the Groth16 pairing equation for
+    /// `public_inputs`. Every G2 point is the generator, so the equation
+    /// reduces to `A = alpha + vk_x + C` in G1; this exercises the verifier's
+    /// arithmetic without a real circuit.
+    #[cfg(feature = "zk")]
+    #[allow(clippy::type_complexity)]
+    fn synthetic_groth16(
+        public_inputs: &[[u8; 32]; 5],
+    ) -> ([u8; 64], [u8; 128], [[u8; 64]; 6], [u8; 64], [u8; 64]) {
+        use pinocchio_airdrop_distributor::utils::{
+            alt_bn128_add, alt_bn128_mul, zk_field_element,
+        };
+
+        // EIP-197 encoding: x_im || x_re || y_im || y_re
+        let g2: [u8; 128] = [
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+            "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+            "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+            "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        ]
+        .concat()
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(core::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect::<Vec<u8>>()
+        .try_into()
+        .unwrap();
+
+        let mut g1 = [0u8; 64];
+        g1[31] = 1;
+        g1[63] = 2;
+        let times = |k: u8| alt_bn128_mul(&g1, &zk_field_element(&[k])).unwrap();
+
+        let alpha = times(3);
+        let ic: [[u8; 64]; 6] = core::array::from_fn(|i| times(5 + i as u8));
+        let proof_c = times(7);
+
+        let mut vk_x = ic[0];
+        for (input, point) in public_inputs.iter().zip(&ic[1..]) {
+            vk_x = alt_bn128_add(&vk_x, &alt_bn128_mul(point, input).unwrap()).unwrap();
+        }
+        let proof_a = alt_bn128_add(&alt_bn128_add(&alpha, &vk_x).unwrap(), &proof_c).unwrap();
+
+        (alpha, g2, ic, proof_a, proof_c)
+    }
+
+    #[cfg(feature = "zk")]
+    #[test]
+    fn test_verify_groth16() {
+        use pinocchio_airdrop_distributor::{
+            instructions::zk_public_inputs, utils::verify_groth16,
+        };
+
+        let merkle_root = [0x01; 32];
+        let mut nullifier_hash = [0x2a; 32];
+        nullifier_hash[0] = 0x0f;
+        let destination = Pubkey::new_from_array([0xee; 32]).to_bytes();
+        let public_inputs = zk_public_inputs(&merkle_root, &nullifier_hash, 1, &destination);
+        let (alpha, g2, ic, proof_a, proof_c) = synthetic_groth16(&public_inputs);
+
+        assert!(verify_groth16(
+            &alpha,
+            &g2,
+            &g2,
+            &g2,
+            &ic,
+            &proof_a,
+            &g2,
+            &proof_c,
+            &public_inputs
+        ));
+
+        // the proof is bound to every public input
+        let other_destination = zk_public_inputs(&merkle_root, &nullifier_hash, 1, &[0xef; 32]);
+        assert!(!verify_groth16(
+            &alpha,
+            &g2,
+            &g2,
+            &g2,
+            &ic,
+            &proof_a,
+            &g2,
+            &proof_c,
+            &other_destination
+        ));
+        let other_tier = zk_public_inputs(&merkle_root, &nullifier_hash, 2, &destination);
+        assert!(!verify_groth16(
+            &alpha,
+            &g2,
+            &g2,
+            &g2,
+            &ic,
+            &proof_a,
+            &g2,
+            &proof_c,
+            &other_tier
+        ));
+
+        // non-canonical inputs are rejected even when the curve math agrees
+        let mut oversized = public_inputs;
+        oversized[1] = [0xff; 32];
+        let (alpha, g2, ic, proof_a, proof_c) = synthetic_groth16(&oversized);
+        assert!(!verify_groth16(
+            &alpha, &g2, &g2, &g2, &ic, &proof_a, &g2, &proof_c, &oversized
+        ));
+
+        println!("✅ Groth16 verification works correctly");
+    }
+
+    #[cfg(feature = "zk")]
+    #[test]
+    fn claim_zk_success() {
+        use pinocchio_airdrop_distributor::{
+            instructions::{zk_public_inputs, ClaimZkInstructionData},
+            states::{Nullifier, ZkConfig},
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let payer = Pubkey::new_from_array([0x06; 32]);
+        let payer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let destination = Pubkey::new_from_array([0x03; 32]);
+        let destination_account = Account::new(0, 0, &system_program);
+
+        let merkle_root = [0x01; 32];
+        let mut nullifier_hash = [0x2a; 32];
+        nullifier_hash[0] = 0x0f;
+        let tier = 1u8;
+        let tier_amounts = [10_000_000u64, 50_000_000, 0, 0];
+        let amount = 500_000_000u64;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let public_inputs =
+            zk_public_inputs(&merkle_root, &nullifier_hash, tier, &destination.to_bytes());
+        let (alpha, g2, ic, proof_a, proof_c) = synthetic_groth16(&public_inputs);
+
+        let (zk_config_address, zk_config_bump) =
+            Pubkey::find_program_address(&[ZkConfig::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+        let zk_config_data = ZkConfig {
+            discriminator: ZkConfig::DISCRIMINATOR,
+            version: [ZkConfig::VERSION],
+            bump: [zk_config_bump],
+            tier_count: [2],
+            tier_amounts: tier_amounts.map(Into::into),
+            vk_alpha_g1: alpha,
+            vk_beta_g2: g2,
+            vk_gamma_g2: g2,
+            vk_delta_g2: g2,
+            vk_ic: ic,
+        };
+        let mut zk_config_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(ZkConfig::LEN),
+            ZkConfig::LEN,
+            &PROGRAM_ID,
+        );
+        zk_config_account.set_data_from_slice(unsafe { to_bytes::<ZkConfig>(&zk_config_data) });
+
+        let (nullifier_address, nullifier_bump) = Pubkey::find_program_address(
+            &[
+                Nullifier::SEED,
+                airdrop_address.as_ref(),
+                nullifier_hash.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        let nullifier_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimZkInstructionData {
+            nullifier_hash,
+            proof_a,
+            proof_b: g2,
+            proof_c,
+            tier,
+            bump: nullifier_bump,
+        };
+
+        let mut data = vec![9];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new_readonly(zk_config_address, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(nullifier_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (zk_config_address, zk_config_account.into()),
+                    (payer, payer_account),
+                    (destination, destination_account),
+                    (nullifier_address, nullifier_account),
+                    (system_program, system_account),
+                    (Pubkey::new_from_array(Config::ADDRESS), Account::default()),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&nullifier_address)
+                        .owner(&PROGRAM_ID)
+                        .space(Nullifier::LEN)
+                        .build(),
+                    Check::account(&destination)
+                        .lamports(tier_amounts[tier as usize])
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
+    }
+
+    #[cfg(feature = "zk")]
+    #[test]
+    fn claim_zk_failure_when_nullifier_bump_is_not_canonical() {
+        use pinocchio_airdrop_distributor::{
+            instructions::{zk_public_inputs, ClaimZkInstructionData},
+            states::{Nullifier, ZkConfig},
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let payer = Pubkey::new_from_array([0x06; 32]);
+        let payer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let destination = Pubkey::new_from_array([0x03; 32]);
+        let destination_account = Account::new(0, 0, &system_program);
+
+        let merkle_root = [0x01; 32];
+        let mut nullifier_hash = [0x2a; 32];
+        nullifier_hash[0] = 0x0f;
+        let tier = 1u8;
+        let tier_amounts = [10_000_000u64, 50_000_000, 0, 0];
+        let amount = 500_000_000u64;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let public_inputs =
+            zk_public_inputs(&merkle_root, &nullifier_hash, tier, &destination.to_bytes());
+        let (alpha, g2, ic, proof_a, proof_c) = synthetic_groth16(&public_inputs);
+
+        let (zk_config_address, zk_config_bump) =
+            Pubkey::find_program_address(&[ZkConfig::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+        let zk_config_data = ZkConfig {
+            discriminator: ZkConfig::DISCRIMINATOR,
+            version: [ZkConfig::VERSION],
+            bump: [zk_config_bump],
+            tier_count: [2],
+            tier_amounts: tier_amounts.map(Into::into),
+            vk_alpha_g1: alpha,
+            vk_beta_g2: g2,
+            vk_gamma_g2: g2,
+            vk_delta_g2: g2,
+            vk_ic: ic,
+        };
+        let mut zk_config_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(ZkConfig::LEN),
+            ZkConfig::LEN,
+            &PROGRAM_ID,
+        );
+        zk_config_account.set_data_from_slice(unsafe { to_bytes::<ZkConfig>(&zk_config_data) });
+
+        // a second address for the same nullifier hash, where a spent
+        // canonical nullifier would not block the claim
+        let (nullifier_address, nullifier_bump) = non_canonical_pda(&[
+            Nullifier::SEED,
+            airdrop_address.as_ref(),
+            nullifier_hash.as_ref(),
+        ]);
+        let nullifier_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimZkInstructionData {
+            nullifier_hash,
+            proof_a,
+            proof_b: g2,
+            proof_c,
+            tier,
+            bump: nullifier_bump,
+        };
+
+        let mut data = vec![9];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new_readonly(zk_config_address, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(nullifier_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (zk_config_address, zk_config_account.into()),
+                    (payer, payer_account),
+                    (destination, destination_account),
+                    (nullifier_address, nullifier_account),
+                    (system_program, system_account),
+                    (Pubkey::new_from_array(Config::ADDRESS), Account::default()),
+                ],
+                &[Check::err(ProgramError::Custom(11))], // invalid_pda
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    #[cfg(feature = "zk")]
+    #[test]
+    fn claim_zk_failure_without_instructions_sysvar() {
+        use pinocchio_airdrop_distributor::{
+            instructions::{zk_public_inputs, ClaimZkInstructionData},
+            states::{Nullifier, ZkConfig},
+        };
+
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let payer = Pubkey::new_from_array([0x06; 32]);
+        let payer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let destination = Pubkey::new_from_array([0x03; 32]);
+        let destination_account = Account::new(0, 0, &system_program);
+
+        let merkle_root = [0x01; 32];
+        let mut nullifier_hash = [0x2a; 32];
+        nullifier_hash[0] = 0x0f;
+        let tier = 1u8;
+        let tier_amounts = [10_000_000u64, 50_000_000, 0, 0];
+        let amount = 500_000_000u64;
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            // no claims through CPI, which needs the instructions sysvar
+            cpi_policy: [AirdropState::CPI_DENY],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        let public_inputs =
+            zk_public_inputs(&merkle_root, &nullifier_hash, tier, &destination.to_bytes());
+        let (alpha, g2, ic, proof_a, proof_c) = synthetic_groth16(&public_inputs);
+
+        let (zk_config_address, zk_config_bump) =
+            Pubkey::find_program_address(&[ZkConfig::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+        let zk_config_data = ZkConfig {
+            discriminator: ZkConfig::DISCRIMINATOR,
+            version: [ZkConfig::VERSION],
+            bump: [zk_config_bump],
+            tier_count: [2],
+            tier_amounts: tier_amounts.map(Into::into),
+            vk_alpha_g1: alpha,
+            vk_beta_g2: g2,
+            vk_gamma_g2: g2,
+            vk_delta_g2: g2,
+            vk_ic: ic,
+        };
+        let mut zk_config_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(ZkConfig::LEN),
+            ZkConfig::LEN,
+            &PROGRAM_ID,
+        );
+        zk_config_account.set_data_from_slice(unsafe { to_bytes::<ZkConfig>(&zk_config_data) });
+
+        let (nullifier_address, nullifier_bump) = Pubkey::find_program_address(
+            &[
+                Nullifier::SEED,
+                airdrop_address.as_ref(),
+                nullifier_hash.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+        let nullifier_account = Account::new(0, 0, &system_program);
+
+        let ix_data = ClaimZkInstructionData {
+            nullifier_hash,
+            proof_a,
+            proof_b: g2,
+            proof_c,
+            tier,
+            bump: nullifier_bump,
+        };
+
+        let mut data = vec![9];
+        data.extend_from_slice(unsafe { to_bytes(&ix_data) });
+
+        let instruction = Instruction::new_with_bytes(
+            PROGRAM_ID,
+            &data,
+            vec![
+                AccountMeta::new(airdrop_address, false),
+                AccountMeta::new(vault_address, false),
+                AccountMeta::new_readonly(zk_config_address, false),
+                AccountMeta::new(payer, true),
+                AccountMeta::new(destination, false),
+                AccountMeta::new(nullifier_address, false),
+                AccountMeta::new_readonly(system_program, false),
+                AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            ],
+        );
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (zk_config_address, zk_config_account.into()),
+                    (payer, payer_account),
+                    (destination, destination_account),
+                    (nullifier_address, nullifier_account),
+                    (system_program, system_account),
+                    (Pubkey::new_from_array(Config::ADDRESS), Account::default()),
+                ],
+                &[Check::err(ProgramError::NotEnoughAccountKeys)],
+            );
+        assert!(result.program_result.is_err());
+    }
+
+    /// Serialize `accounts` the way the runtime lays out program input and
+    /// let pinocchio parse it, giving the `AccountInfo`s a caller program
+    /// would hand to the `cpi` helpers. Repeated keys become duplicates.
+    fn account_infos(
+        accounts: &[(AccountMeta, Account)],
+    ) -> Vec<pinocchio::account_info::AccountInfo> {
+        use core::mem::MaybeUninit;
+        use pinocchio::account_info::{AccountInfo, MAX_PERMITTED_DATA_INCREASE};
+
+        let mut input = (accounts.len() as u64).to_le_bytes().to_vec();
+        for (index, (meta, account)) in accounts.iter().enumerate() {
+            if let Some(original) = accounts[..index]
+                .iter()
+                .position(|(other, _)| other.pubkey == meta.pubkey)
+            {
+                input.push(original as u8);
+                input.extend_from_slice(&[0u8; 7]);
+                continue;
+            }
+
+            input.extend_from_slice(&[
+                u8::MAX,
+                meta.is_signer as u8,
+                meta.is_writable as u8,
+                account.executable as u8,
+            ]);
+            input.extend_from_slice(&[0u8; 4]);
+            input.extend_from_slice(meta.pubkey.as_ref());
+            input.extend_from_slice(account.owner.as_ref());
+            input.extend_from_slice(&account.lamports.to_le_bytes());
+            input.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+            input.extend_from_slice(&account.data);
+            input.resize(input.len() + MAX_PERMITTED_DATA_INCREASE, 0);
+            input.resize(input.len().next_multiple_of(8), 0);
+            input.extend_from_slice(&account.rent_epoch.to_le_bytes());
+        }
+        input.extend_from_slice(&0u64.to_le_bytes());
+        input.extend_from_slice(PROGRAM_ID.as_ref());
+
+        // the infos point into the buffer, which must stay aligned and alive
+        let buffer: &'static mut [u64] = Vec::leak(vec![0u64; input.len().div_ceil(8)]);
+        let bytes = buffer.as_mut_ptr() as *mut u8;
+        unsafe { core::ptr::copy_nonoverlapping(input.as_ptr(), bytes, input.len()) };
+
+        let mut infos = [const { MaybeUninit::<AccountInfo>::uninit() }; 16];
+        let (_, count, _) = unsafe { pinocchio::entrypoint::deserialize(bytes, &mut infos) };
+        infos[..count]
+            .iter()
+            .map(|info| unsafe { info.assume_init_ref().clone() })
+            .collect()
+    }
+
+    /// Convert an instruction built by a `cpi` helper, checking that the
+    /// account infos it would pass follow its metas.
+    fn from_cpi(
+        instruction: &pinocchio::instruction::Instruction,
+        accounts: &[&pinocchio::account_info::AccountInfo],
+    ) -> Instruction {
+        assert_eq!(instruction.accounts.len(), accounts.len());
+        let metas = instruction
+            .accounts
+            .iter()
+            .zip(accounts)
+            .map(|(meta, account)| {
+                assert_eq!(meta.pubkey, account.key());
+                AccountMeta {
+                    pubkey: Pubkey::new_from_array(*meta.pubkey),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                }
+            })
+            .collect();
+
+        Instruction::new_with_bytes(
+            Pubkey::new_from_array(*instruction.program_id),
+            instruction.data,
+            metas,
+        )
+    }
+
+    #[test]
+    fn cpi_claim_instruction_layout() {
+        use pinocchio_airdrop_distributor::{
+            errors::AirdropProgramError, instructions::ClaimAirdrop, utils::MAX_PROOF_LEN,
+        };
+
+        // every account in the order `ClaimAirdropAccounts` parses them
+        let metas = [
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), true),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ];
+        let accounts: Vec<_> = metas
+            .iter()
+            .map(|meta| (meta.clone(), Account::default()))
+            .collect();
+        let infos = account_infos(&accounts);
+
+        let proof = [[0x11; 32], [0x22; 32]];
+        let mut claim = cpi::Claim {
+            airdrop_state: &infos[0],
+            vault: &infos[1],
+            signer: &infos[2],
+            recipient: &infos[3],
+            user_claim: &infos[4],
+            system_program: &infos[5],
+            redirect: &infos[6],
+            config: &infos[7],
+            treasury: Some(&infos[8]),
+            legacy_claim: Some(&infos[9]),
+            instructions_sysvar: Some(&infos[10]),
+            amount: 50_000_000,
+            leaf_index: 7,
+            bump: 254,
+            proof: &proof,
+        };
+
+        let instruction = claim.with_instruction(from_cpi).unwrap();
+        assert_eq!(instruction.program_id, PROGRAM_ID);
+        assert_eq!(instruction.accounts, metas);
+
+        assert_eq!(instruction.data[0], *ClaimAirdrop::DISCRIMINATOR);
+        let ix_data = ClaimAirdropInstructionData::try_from(&instruction.data[1..]).unwrap();
+        assert_eq!({ ix_data.amount }, 50_000_000);
+        assert_eq!({ ix_data.leaf_index }, 7);
+        assert_eq!(ix_data.bump, 254);
+        assert_eq!(ix_data.proof_len, 2);
+        assert_eq!(
+            &instruction.data[1 + ClaimAirdropInstructionData::LEN..],
+            proof.concat()
+        );
+
+        // without a treasury or a legacy status, the sysvar follows the config
+        claim.treasury = None;
+        claim.legacy_claim = None;
+        let instruction = claim.with_instruction(from_cpi).unwrap();
+        assert_eq!(instruction.accounts[..8], metas[..8]);
+        assert_eq!(instruction.accounts[8..], metas[10..]);
+
+        let long_proof = [[0u8; 32]; MAX_PROOF_LEN + 1];
+        claim.proof = &long_proof;
+        assert_eq!(
+            claim.with_instruction(from_cpi).err(),
+            Some(AirdropProgramError::ProofTooLong.into())
+        );
+    }
+
+    #[test]
+    fn cpi_verify_claim_instruction_layout() {
+        use pinocchio_airdrop_distributor::instructions::{
+            VerifyClaim, VerifyClaimInstructionData,
+        };
+
+        // every account in the order `VerifyClaimAccounts` parses them
+        let metas = [
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_from_array(Config::ADDRESS), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ];
+        let accounts: Vec<_> = metas
+            .iter()
+            .map(|meta| (meta.clone(), Account::default()))
+            .collect();
+        let infos = account_infos(&accounts);
+
+        let proof = [[0x33; 32]];
+        let mut verify = cpi::VerifyClaim {
+            airdrop_state: &infos[0],
+            recipient: &infos[1],
+            user_claim: &infos[2],
+            redirect: &infos[3],
+            config: &infos[4],
+            claim_authority: Some(&infos[5]),
+            legacy_claim: Some(&infos[6]),
+            amount: 75_000_000,
+            leaf_index: 2,
+            bump: 253,
+            proof: &proof,
+        };
+
+        let instruction = verify.with_instruction(from_cpi).unwrap();
+        assert_eq!(instruction.accounts, metas);
+
+        assert_eq!(instruction.data[0], *VerifyClaim::DISCRIMINATOR);
+        let ix_data = VerifyClaimInstructionData::try_from(&instruction.data[1..]).unwrap();
+        assert_eq!({ ix_data.amount }, 75_000_000);
+        assert_eq!({ ix_data.leaf_index }, 2);
+        assert_eq!(ix_data.bump, 253);
+        assert_eq!(ix_data.proof_len, 1);
+        assert_eq!(
+            &instruction.data[1 + VerifyClaimInstructionData::LEN..],
+            proof.concat()
+        );
+
+        // a legacy status without a claim authority takes its slot
+        verify.claim_authority = None;
+        let instruction = verify.with_instruction(from_cpi).unwrap();
+        assert_eq!(instruction.accounts[..5], metas[..5]);
+        assert_eq!(instruction.accounts[5..], metas[6..]);
+    }
+
+    #[test]
+    fn cpi_deposit_instruction_layout() {
+        use pinocchio_airdrop_distributor::instructions::{Deposit, DepositInstructionData};
+
+        // every account in the order `DepositAccounts` parses them
+        let metas = [
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new(Pubkey::new_unique(), true),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ];
+        let accounts: Vec<_> = metas
+            .iter()
+            .map(|meta| (meta.clone(), Account::default()))
+            .collect();
+        let infos = account_infos(&accounts);
+
+        let deposit = cpi::Deposit {
+            airdrop_state: &infos[0],
+            vault: &infos[1],
+            depositor: &infos[2],
+            system_program: &infos[3],
+            amount: 42_000,
+        };
+
+        let instruction =
+            deposit.with_instruction(|instruction, accounts| from_cpi(instruction, accounts));
+        assert_eq!(instruction.accounts, metas);
+
+        assert_eq!(instruction.data[0], *Deposit::DISCRIMINATOR);
+        let ix_data = DepositInstructionData::try_from(&instruction.data[1..]).unwrap();
+        assert_eq!({ ix_data.amount }, 42_000);
+    }
+
+    #[test]
+    fn cpi_claim_success() {
+        let mollusk = get_mollusk();
+
+        let (system_program, system_account) =
+            mollusk_svm::program::keyed_account_for_system_program();
+
+        let maker = Pubkey::new_from_array([0x02; 32]);
+
+        let claimer = Pubkey::new_from_array([0x03; 32]);
+        let claimer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let treasury = Pubkey::new_from_array([0x04; 32]);
+        let treasury_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+        let airdrop_recipients = vec![
+            (Pubkey::new_unique(), 100_000_000u64),
+            (claimer, 50_000_000u64),
+            (Pubkey::new_unique(), 75_000_000u64),
+        ];
+        let merkle_root = create_merkle_root(&airdrop_recipients);
+        let amount: u64 = airdrop_recipients.iter().map(|(_, amt)| amt).sum();
+
+        let (airdrop_address, airdrop_account_bump) =
+            Pubkey::find_program_address(&[AirdropState::SEED], &PROGRAM_ID);
+        let (vault_address, vault_bump) =
+            Pubkey::find_program_address(&[Vault::SEED, airdrop_address.as_ref()], &PROGRAM_ID);
+
+        let airdrop_account_data = AirdropState {
+            discriminator: AirdropState::DISCRIMINATOR,
+            version: [AirdropState::VERSION],
+            authority: maker.to_bytes(),
+            merkle_root,
+            airdrop_amount: amount.into(),
+            amount_claimed: 0u64.into(),
+            bump: [airdrop_account_bump],
+            closed: [0],
+            operator: [0u8; 32],
+            distribution_mode: [AirdropState::MODE_SELF_CLAIM],
+            hash_fn: [HashFunction::Keccak256 as u8],
+            claim_key: [AirdropState::CLAIM_KEY_CLAIMER],
+            vault_bump: [vault_bump],
+            cpi_policy: [AirdropState::CPI_ALLOW_ALL],
+            cpi_caller_count: [0],
+            cpi_callers: [[0u8; 32]; AirdropState::MAX_CPI_CALLERS],
+        };
+        let lamport_for_rent = mollusk.sysvars.rent.minimum_balance(AirdropState::LEN);
+
+        let vault_rent = mollusk.sysvars.rent.minimum_balance(0);
+        let vault_account = Account::new(vault_rent + amount, 0, &PROGRAM_ID);
+
+        let mut airdrop_account =
+            AccountSharedData::new(lamport_for_rent, AirdropState::LEN, &PROGRAM_ID);
+
+        airdrop_account
+            .set_data_from_slice(unsafe { to_bytes::<AirdropState>(&airdrop_account_data) });
+
+        // 2.5% withheld from every claimed amount
+        let redirect_address = redirect_of(&airdrop_address, &claimer);
+        let redirect_account = Account::new(0, 0, &system_program);
+
+        let config_address = Pubkey::new_from_array(Config::ADDRESS);
+        let config_data = Config {
+            discriminator: Config::DISCRIMINATOR,
+            version: [Config::VERSION],
+            admin: maker.to_bytes(),
+            treasury: treasury.to_bytes(),
+            fee_mode: [Config::FEE_BPS],
+            fee: 250u64.into(),
+            fee_source: [Config::FEE_FROM_AMOUNT],
+        };
+        let mut config_account = AccountSharedData::new(
+            mollusk.sysvars.rent.minimum_balance(Config::LEN),
+            Config::LEN,
+            &PROGRAM_ID,
+        );
+        config_account.set_data_from_slice(unsafe { to_bytes::<Config>(&config_data) });
+
+        let leaf_index = 1;
+        let claimed = airdrop_recipients[leaf_index].1;
+        let fee = claimed * 250 / 10_000;
+        let proof = create_merkle_proof(&airdrop_recipients, leaf_index);
+
+        let (user_claim_address, user_claim_account_bump) = Pubkey::find_program_address(
+            &[
+                ClaimStatus::SEED,
+                airdrop_address.as_ref(),
+                claimer.as_ref(),
+            ],
+            &PROGRAM_ID,
+        );
+
+        let user_claim_account = Account::new(0, 0, &system_program);
+
+        // the claim as a caller program builds it, run through the parser
+        let accounts = [
+            (
+                AccountMeta::new(airdrop_address, false),
+                airdrop_account.clone().into(),
+            ),
+            (
+                AccountMeta::new(vault_address, false),
+                vault_account.clone(),
+            ),
+            (AccountMeta::new(claimer, true), claimer_account.clone()),
+            (AccountMeta::new(claimer, false), claimer_account.clone()),
+            (
+                AccountMeta::new(user_claim_address, false),
+                user_claim_account.clone(),
+            ),
+            (
+                AccountMeta::new_readonly(system_program, false),
+                system_account.clone(),
+            ),
+            (
+                AccountMeta::new_readonly(redirect_address, false),
+                redirect_account.clone(),
+            ),
+            (
+                AccountMeta::new_readonly(config_address, false),
+                config_account.clone().into(),
+            ),
+            (AccountMeta::new(treasury, false), treasury_account.clone()),
+        ];
+        let infos = account_infos(&accounts);
+
+        let instruction = cpi::Claim {
+            airdrop_state: &infos[0],
+            vault: &infos[1],
+            signer: &infos[2],
+            recipient: &infos[3],
+            user_claim: &infos[4],
+            system_program: &infos[5],
+            redirect: &infos[6],
+            config: &infos[7],
+            treasury: Some(&infos[8]),
+            legacy_claim: None,
+            instructions_sysvar: None,
+            amount: claimed,
+            leaf_index: leaf_index as u64,
+            bump: user_claim_account_bump,
+            proof: &proof,
+        }
+        .with_instruction(from_cpi)
+        .unwrap();
+
+        let result: mollusk_svm::result::InstructionResult = mollusk
+            .process_and_validate_instruction(
+                &instruction,
+                &[
+                    (airdrop_address, airdrop_account.into()),
+                    (vault_address, vault_account),
+                    (claimer, claimer_account),
+                    (user_claim_address, user_claim_account),
+                    (system_program, system_account),
+                    (redirect_address, redirect_account),
+                    (config_address, config_account.into()),
+                    (treasury, treasury_account),
+                ],
+                &[
+                    Check::success(),
+                    Check::account(&vault_address)
+                        .lamports(vault_rent + amount - claimed)
+                        .build(),
+                    Check::account(&treasury)
+                        .lamports(LAMPORTS_PER_SOL + fee)
+                        .build(),
+                    Check::account(&user_claim_address)
+                        .owner(&PROGRAM_ID)
+                        .build(),
+                ],
+            );
+        assert!(result.program_result == ProgramResult::Success);
     }
 }